@@ -0,0 +1,33 @@
+/// Adds two integers using only bitwise operations, wrapping on overflow.
+pub fn adder(a: u32, b: u32) -> u32 {
+    let mut x = a;
+    let mut y = b;
+    while y != 0 {
+        let carry = (x & y) << 1;
+        x ^= y;
+        y = carry;
+    }
+    x
+}
+
+/// Multiplies two integers with the Russian peasant algorithm, wrapping on overflow.
+// Time: O(log b * log a), Space: O(1)
+pub fn multiplier(a: u32, b: u32) -> u32 {
+    let mut result = 0;
+    let mut x = a;
+    let mut y = b;
+    while y > 0 {
+        if y & 1 == 1 {
+            // If y is odd, add x to result
+            result = adder(result, x);
+        }
+        x <<= 1; // Double x
+        y >>= 1; // Halve y
+    }
+    result
+}
+
+/// Converts `n` to its Gray code, where consecutive values differ by one bit.
+pub fn gray_code(n: u32) -> u32 {
+    n ^ (n >> 1) // XOR with right-shifted n
+}
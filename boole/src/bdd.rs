@@ -0,0 +1,721 @@
+//! Reduced ordered binary decision diagrams.
+//!
+//! A BDD is a decision graph where every path tests variables in the same
+//! order, identical subgraphs are stored once and no node has equal
+//! children. Under a fixed order each function has exactly one such graph,
+//! so equivalence is comparing two handles, and tautology or satisfiability
+//! are comparisons with the constants.
+//!
+//! Edges may be complemented: a handle carries a flag negating the function
+//! it points to, so `!f` costs nothing and `f` and `!f` share their nodes.
+//! To keep graphs canonical, the high (`1`) edge of a node is never
+//! complemented.
+
+use std::collections::HashMap;
+
+use crate::formula::{Assignment, Formula};
+use crate::models::Count;
+use crate::var::Var;
+
+/// A function held by a [`Manager`]. Only meaningful with the manager that
+/// created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bdd(u32);
+
+impl Bdd {
+    pub const TRUE: Bdd = Bdd(0);
+    pub const FALSE: Bdd = Bdd(1);
+
+    fn node(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    fn is_complemented(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    fn regular(self) -> Bdd {
+        Bdd(self.0 & !1)
+    }
+
+    pub fn is_constant(self) -> bool {
+        self.node() == 0
+    }
+}
+
+impl std::ops::Not for Bdd {
+    type Output = Bdd;
+
+    fn not(self) -> Bdd {
+        Bdd(self.0 ^ 1)
+    }
+}
+
+// The only terminal is node 0, the constant `true`; its variable sorts last.
+const TERMINAL: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    var: u32,
+    low: Bdd,
+    high: Bdd,
+}
+
+/// Owns the nodes of every BDD built with it, so equal functions get equal
+/// handles.
+pub struct Manager {
+    nodes: Vec<Node>,
+    // Nodes by variable and children, so each exists once
+    unique: HashMap<(u32, Bdd, Bdd), Bdd>,
+    // Results of `ite`, the operation every other one is built on
+    computed: HashMap<(Bdd, Bdd, Bdd), Bdd>,
+    vars: Vec<Var>,
+    index: HashMap<Var, u32>,
+    // Position of each variable in the order, and variable at each position
+    level: Vec<u32>,
+    order: Vec<u32>,
+}
+
+impl Default for Manager {
+    fn default() -> Manager {
+        Manager::new()
+    }
+}
+
+impl Manager {
+    pub fn new() -> Manager {
+        Manager {
+            nodes: vec![Node { var: TERMINAL, low: Bdd::TRUE, high: Bdd::TRUE }],
+            unique: HashMap::new(),
+            computed: HashMap::new(),
+            vars: Vec::new(),
+            index: HashMap::new(),
+            level: Vec::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// The function true when `var` is, adding `var` below every known
+    /// variable in the order if it is new.
+    pub fn var(&mut self, var: Var) -> Bdd {
+        let index = self.declare(var);
+        self.make(index, Bdd::FALSE, Bdd::TRUE)
+    }
+
+    /// Adds variables to the bottom of the order, skipping known ones. Call
+    /// before [`build`](Manager::build) to choose the order, for instance
+    /// with [`static_order`].
+    pub fn add_vars(&mut self, vars: &[Var]) {
+        for &var in vars {
+            self.declare(var);
+        }
+    }
+
+    fn declare(&mut self, var: Var) -> u32 {
+        if let Some(&index) = self.index.get(&var) {
+            return index;
+        }
+        let index = self.vars.len() as u32;
+        self.vars.push(var);
+        self.index.insert(var, index);
+        self.level.push(index);
+        self.order.push(index);
+        index
+    }
+
+    /// The variables in their order, first tested first.
+    pub fn order(&self) -> Vec<Var> {
+        self.order.iter().map(|&index| self.vars[index as usize]).collect()
+    }
+
+    /// Builds the BDD of a formula. Its variables not yet known are added
+    /// in name order.
+    ///
+    /// ```
+    /// use boole::bdd::Manager;
+    ///
+    /// let mut manager = Manager::new();
+    /// let f = manager.build(&boole::parse("AB>BC>&").unwrap());
+    /// let g = manager.build(&boole::parse("A!B|B!C|&").unwrap());
+    /// assert_eq!(f, g);
+    /// ```
+    pub fn build(&mut self, f: &Formula) -> Bdd {
+        for var in f.vars() {
+            self.declare(var);
+        }
+        self.build_node(f)
+    }
+
+    fn build_node(&mut self, f: &Formula) -> Bdd {
+        match f {
+            Formula::Const(true) => Bdd::TRUE,
+            Formula::Const(false) => Bdd::FALSE,
+            Formula::Var(var) => self.var(*var),
+            Formula::Not(a) => !self.build_node(a),
+            _ => {
+                let (a, b) = f.operands().unwrap();
+                let (a, b) = (self.build_node(a), self.build_node(b));
+                self.apply(f.symbol().unwrap(), a, b)
+            }
+        }
+    }
+
+    /// Combines two functions with one of the formula operators `&`, `|`,
+    /// `^`, `>` or `=`. Negation is `!f`.
+    pub fn apply(&mut self, op: char, f: Bdd, g: Bdd) -> Bdd {
+        match op {
+            '&' => self.ite(f, g, Bdd::FALSE),
+            '|' => self.ite(f, Bdd::TRUE, g),
+            '^' => self.ite(f, !g, g),
+            '>' => self.ite(f, g, Bdd::TRUE),
+            '=' => self.ite(f, g, !g),
+            _ => panic!("unknown operator '{}'", op),
+        }
+    }
+
+    pub fn and(&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.apply('&', f, g)
+    }
+
+    pub fn or(&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.apply('|', f, g)
+    }
+
+    /// If-then-else: `g` where `f` is true and `h` where it is false.
+    pub fn ite(&mut self, f: Bdd, g: Bdd, h: Bdd) -> Bdd {
+        // Constant cases
+        if f == Bdd::TRUE || g == h {
+            return g;
+        }
+        if f == Bdd::FALSE {
+            return h;
+        }
+        let g = if g == f { Bdd::TRUE } else if g == !f { Bdd::FALSE } else { g };
+        let h = if h == f { Bdd::FALSE } else if h == !f { Bdd::TRUE } else { h };
+        if g == Bdd::TRUE && h == Bdd::FALSE {
+            return f;
+        }
+        if g == Bdd::FALSE && h == Bdd::TRUE {
+            return !f;
+        }
+
+        // Equivalent calls share one cache entry: `f` and `g` regular
+        let (f, g, h) = if f.is_complemented() { (!f, h, g) } else { (f, g, h) };
+        let (g, h, negate) = if g.is_complemented() { (!g, !h, true) } else { (g, h, false) };
+        if let Some(&result) = self.computed.get(&(f, g, h)) {
+            return if negate { !result } else { result };
+        }
+
+        let level = self.level_of(f).min(self.level_of(g)).min(self.level_of(h));
+        let var = self.order[level as usize];
+        let (f0, f1) = self.cofactors(f, level);
+        let (g0, g1) = self.cofactors(g, level);
+        let (h0, h1) = self.cofactors(h, level);
+        let high = self.ite(f1, g1, h1);
+        let low = self.ite(f0, g0, h0);
+        let result = self.make(var, low, high);
+
+        self.computed.insert((f, g, h), result);
+        if negate {
+            !result
+        } else {
+            result
+        }
+    }
+
+    fn level_of(&self, f: Bdd) -> u32 {
+        match self.nodes[f.node()].var {
+            TERMINAL => TERMINAL,
+            var => self.level[var as usize],
+        }
+    }
+
+    // The functions `f` when the variable at `level` is 0 and 1.
+    fn cofactors(&self, f: Bdd, level: u32) -> (Bdd, Bdd) {
+        if self.level_of(f) != level {
+            return (f, f);
+        }
+        let node = self.nodes[f.node()];
+        if f.is_complemented() {
+            (!node.low, !node.high)
+        } else {
+            (node.low, node.high)
+        }
+    }
+
+    // The node testing `var`, or its children if they are equal.
+    fn make(&mut self, var: u32, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+        if high.is_complemented() {
+            return !self.make(var, !low, !high);
+        }
+        if let Some(&node) = self.unique.get(&(var, low, high)) {
+            return node;
+        }
+        let node = Bdd((self.nodes.len() as u32) << 1);
+        self.nodes.push(Node { var, low, high });
+        self.unique.insert((var, low, high), node);
+        node
+    }
+
+    /// `f` with `var` fixed to `value`.
+    pub fn restrict(&mut self, f: Bdd, var: Var, value: bool) -> Bdd {
+        let Some(&index) = self.index.get(&var) else {
+            return f;
+        };
+        self.restrict_node(f, self.level[index as usize], value, &mut HashMap::new())
+    }
+
+    fn restrict_node(&mut self, f: Bdd, level: u32, value: bool, memo: &mut HashMap<Bdd, Bdd>) -> Bdd {
+        let top = self.level_of(f);
+        if top > level {
+            return f;
+        }
+        if top == level {
+            let (low, high) = self.cofactors(f, level);
+            return if value { high } else { low };
+        }
+        if let Some(&result) = memo.get(&f) {
+            return result;
+        }
+        let (low, high) = self.cofactors(f, top);
+        let low = self.restrict_node(low, level, value, memo);
+        let high = self.restrict_node(high, level, value, memo);
+        let result = self.make(self.order[top as usize], low, high);
+        memo.insert(f, result);
+        result
+    }
+
+    /// ∃var. f: true where `f` is for some value of `var`.
+    pub fn exists(&mut self, f: Bdd, var: Var) -> Bdd {
+        let (low, high) = (self.restrict(f, var, false), self.restrict(f, var, true));
+        self.or(low, high)
+    }
+
+    /// ∀var. f: true where `f` is for both values of `var`.
+    pub fn forall(&mut self, f: Bdd, var: Var) -> Bdd {
+        let (low, high) = (self.restrict(f, var, false), self.restrict(f, var, true));
+        self.and(low, high)
+    }
+
+    /// Evaluates `f`; variables missing from the assignment read as false.
+    pub fn eval(&self, f: Bdd, assignment: &Assignment) -> bool {
+        let mut f = f;
+        while !f.is_constant() {
+            let node = self.nodes[f.node()];
+            let value = assignment.get(&self.vars[node.var as usize]).copied().unwrap_or(false);
+            let child = if value { node.high } else { node.low };
+            f = if f.is_complemented() { !child } else { child };
+        }
+        f == Bdd::TRUE
+    }
+
+    /// The number of assignments of all the manager's variables making `f`
+    /// true, counted in one pass over the graph.
+    ///
+    /// ```
+    /// use boole::bdd::Manager;
+    ///
+    /// let mut manager = Manager::new();
+    /// let f = manager.build(&boole::parse("AB|C&").unwrap());
+    /// assert_eq!(manager.sat_count(f), 3u64.into());
+    /// ```
+    pub fn sat_count(&self, f: Bdd) -> Count {
+        let mut memo = HashMap::new();
+        let (ones, zeros) = self.count(f.regular(), &mut memo);
+        let count = if f.is_complemented() { zeros } else { ones };
+        count.mul(&Count::power_of_two(self.gap(0, f)))
+    }
+
+    // The number of true and false assignments of the variables from the
+    // level of `f` down.
+    fn count(&self, f: Bdd, memo: &mut HashMap<Bdd, (Count, Count)>) -> (Count, Count) {
+        if f.is_constant() {
+            return (Count::from(1), Count::from(0));
+        }
+        if let Some(counts) = memo.get(&f) {
+            return counts.clone();
+        }
+        let node = self.nodes[f.node()];
+        let level = self.level[node.var as usize] + 1;
+        let mut ones = Count::from(0);
+        let mut zeros = Count::from(0);
+        for child in [node.low, node.high] {
+            let (child_ones, child_zeros) = self.count(child.regular(), memo);
+            let (child_ones, child_zeros) =
+                if child.is_complemented() { (child_zeros, child_ones) } else { (child_ones, child_zeros) };
+            let scale = Count::power_of_two(self.gap(level, child));
+            ones = ones.add(&child_ones.mul(&scale));
+            zeros = zeros.add(&child_zeros.mul(&scale));
+        }
+        memo.insert(f, (ones.clone(), zeros.clone()));
+        (ones, zeros)
+    }
+
+    // The number of levels skipped between `level` and the node of `f`.
+    fn gap(&self, level: u32, f: Bdd) -> usize {
+        let below = self.level_of(f).min(self.vars.len() as u32);
+        (below - level) as usize
+    }
+
+    /// Some assignment of the manager's variables making `f` true, `None`
+    /// if `f` is false. Variables the path does not test are set false.
+    pub fn any_sat(&self, f: Bdd) -> Option<Assignment> {
+        if f == Bdd::FALSE {
+            return None;
+        }
+        let mut model: Assignment = self.vars.iter().map(|&var| (var, false)).collect();
+        let mut f = f;
+        while !f.is_constant() {
+            let node = self.nodes[f.node()];
+            let flip = |child: Bdd| if f.is_complemented() { !child } else { child };
+            // Every non-constant function is satisfiable, so only a false
+            // constant child is a dead end
+            let value = flip(node.low) == Bdd::FALSE;
+            model.insert(self.vars[node.var as usize], value);
+            f = flip(if value { node.high } else { node.low });
+        }
+        Some(model)
+    }
+
+    /// The number of nodes of `f`, the terminal included.
+    pub fn size(&self, f: Bdd) -> usize {
+        self.size_of(&[f])
+    }
+
+    /// The number of nodes shared by several functions.
+    pub fn size_of(&self, roots: &[Bdd]) -> usize {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack: Vec<usize> = roots.iter().map(|f| f.node()).collect();
+        let mut count = 0;
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut seen[node], true) {
+                continue;
+            }
+            count += 1;
+            if node != 0 {
+                stack.push(self.nodes[node].low.node());
+                stack.push(self.nodes[node].high.node());
+            }
+        }
+        count
+    }
+
+    /// Reorders the variables with Rudell's sifting to shrink the graphs of
+    /// `roots`, which are updated in place. Other handles from this manager
+    /// become invalid.
+    ///
+    /// Each variable in turn, those with the most nodes first, is moved
+    /// through every level by swapping adjacent levels, and left where the
+    /// graphs were smallest. A swap only rewrites the nodes of the two levels.
+    pub fn sift(&mut self, roots: &mut [Bdd]) {
+        self.collect(roots);
+        let mut vars: Vec<u32> = (0..self.vars.len() as u32).collect();
+        let mut counts = vec![0; self.vars.len()];
+        for node in &self.nodes[1..] {
+            counts[node.var as usize] += 1;
+        }
+        vars.sort_by_key(|&var| std::cmp::Reverse(counts[var as usize]));
+
+        for var in vars {
+            self.sift_var(var, roots);
+            self.collect(roots);
+        }
+    }
+
+    fn sift_var(&mut self, var: u32, roots: &[Bdd]) {
+        let last = self.vars.len() - 1;
+        let start = self.level[var as usize] as usize;
+        let mut best = (self.size_of(roots), start);
+        // Give up on a direction once the graphs double
+        let limit = |size: usize, best: usize| size > 2 * best;
+
+        // Towards the nearer end first, then all the way to the other one
+        let down_first = last - start < start;
+        for down in [down_first, !down_first] {
+            loop {
+                let level = self.level[var as usize] as usize;
+                if (down && level == last) || (!down && level == 0) {
+                    break;
+                }
+                self.swap(if down { level } else { level - 1 });
+                let size = self.size_of(roots);
+                if size < best.0 {
+                    best = (size, self.level[var as usize] as usize);
+                }
+                if limit(size, best.0) {
+                    break;
+                }
+            }
+        }
+
+        while (self.level[var as usize] as usize) < best.1 {
+            self.swap(self.level[var as usize] as usize);
+        }
+        while (self.level[var as usize] as usize) > best.1 {
+            self.swap(self.level[var as usize] as usize - 1);
+        }
+    }
+
+    // Swaps the variables at `level` and `level + 1`. Each node of the upper
+    // variable `x` depending on the lower one `y` is rewritten in place to
+    // test `y` first, so it keeps standing for the same function.
+    fn swap(&mut self, level: usize) {
+        let (x, y) = (self.order[level], self.order[level + 1]);
+        let cofactors = |nodes: &[Node], f: Bdd| {
+            let node = nodes[f.node()];
+            match (node.var == y, f.is_complemented()) {
+                (false, _) => (f, f),
+                (true, false) => (node.low, node.high),
+                (true, true) => (!node.low, !node.high),
+            }
+        };
+
+        for index in 1..self.nodes.len() {
+            let node = self.nodes[index];
+            if node.var != x {
+                continue;
+            }
+            let (f00, f01) = cofactors(&self.nodes, node.low);
+            let (f10, f11) = cofactors(&self.nodes, node.high);
+            if (f00, f01) == (node.low, node.low) && (f10, f11) == (node.high, node.high) {
+                continue;
+            }
+            self.unique.remove(&(x, node.low, node.high));
+            // `f11` comes from the regular high edge, so `high` is regular too
+            let low = self.make(x, f00, f10);
+            let high = self.make(x, f01, f11);
+            self.nodes[index] = Node { var: y, low, high };
+            self.unique.insert((y, low, high), Bdd((index as u32) << 1));
+        }
+
+        self.order.swap(level, level + 1);
+        self.level[x as usize] = level as u32 + 1;
+        self.level[y as usize] = level as u32;
+    }
+
+    // Drops the nodes unreachable from `roots`, renumbering the others.
+    fn collect(&mut self, roots: &mut [Bdd]) {
+        let mut copies = HashMap::from([(0, Bdd::TRUE)]);
+        let mut nodes = vec![self.nodes[0]];
+        for root in roots.iter_mut() {
+            *root = self.copy(*root, &mut nodes, &mut copies);
+        }
+        self.unique = (1..nodes.len()).map(|i| ((nodes[i].var, nodes[i].low, nodes[i].high), Bdd((i as u32) << 1))).collect();
+        self.nodes = nodes;
+        self.computed.clear();
+    }
+
+    // The edge `f` into the nodes being copied, by node index of the copies.
+    fn copy(&self, f: Bdd, nodes: &mut Vec<Node>, copies: &mut HashMap<usize, Bdd>) -> Bdd {
+        let copy = match copies.get(&f.node()) {
+            Some(&copy) => copy,
+            None => {
+                let Node { var, low, high } = self.nodes[f.node()];
+                let (low, high) = (self.copy(low, nodes, copies), self.copy(high, nodes, copies));
+                let copy = Bdd((nodes.len() as u32) << 1);
+                nodes.push(Node { var, low, high });
+                copies.insert(f.node(), copy);
+                copy
+            }
+        };
+        if f.is_complemented() {
+            !copy
+        } else {
+            copy
+        }
+    }
+
+    /// The graph of `f` in Graphviz format, one rank per variable. Edges to
+    /// the `0` child are dashed, and complemented edges end in a hollow dot.
+    pub fn to_dot(&self, f: Bdd) -> String {
+        let mut out = String::from("digraph bdd {\n  f [shape=none];\n");
+        let edge = |from: String, to: Bdd, dashed: bool| {
+            let mut style = Vec::new();
+            if dashed {
+                style.push("style=dashed");
+            }
+            if to.is_complemented() {
+                style.push("arrowhead=odot");
+            }
+            let style = if style.is_empty() { String::new() } else { format!(" [{}]", style.join(", ")) };
+            format!("  {} -> n{}{};\n", from, to.node(), style)
+        };
+        out += &edge("f".to_string(), f, false);
+
+        let mut ranks: Vec<Vec<usize>> = vec![Vec::new(); self.vars.len()];
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![f.node()];
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut seen[node], true) {
+                continue;
+            }
+            if node == 0 {
+                out += "  n0 [shape=box, label=\"1\"];\n";
+                continue;
+            }
+            let Node { var, low, high } = self.nodes[node];
+            ranks[self.level[var as usize] as usize].push(node);
+            out += &format!("  n{} [label=\"{}\"];\n", node, escape(self.vars[var as usize].name()));
+            out += &edge(format!("n{}", node), low, true);
+            out += &edge(format!("n{}", node), high, false);
+            stack.extend([high.node(), low.node()]);
+        }
+
+        for rank in ranks.iter().filter(|rank| rank.len() > 1) {
+            let nodes: Vec<String> = rank.iter().map(|node| format!("n{}", node)).collect();
+            out += &format!("  {{ rank=same; {}; }}\n", nodes.join("; "));
+        }
+        out += "}\n";
+        out
+    }
+}
+
+/// A variable order for the BDD of `f`: the order in which variables first
+/// appear in its RPN. Variables used together in a subformula end up close,
+/// which keeps the graph small for formulas like `(x0 & y0) | (x1 & y1) | ...`,
+/// exponential in name order.
+pub fn static_order(f: &Formula) -> Vec<Var> {
+    let mut order = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut stack = vec![f];
+    while let Some(f) = stack.pop() {
+        match f {
+            Formula::Var(var) if seen.insert(*var) => order.push(*var),
+            Formula::Not(a) => stack.push(a),
+            _ => {
+                if let Some((a, b)) = f.operands() {
+                    stack.extend([b, a]);
+                }
+            }
+        }
+    }
+    order
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn satisfiability_without_search() {
+        let tests = [
+            ("AB|", true),
+            ("AB&", true),
+            ("AA!&", false),
+            ("AA^", false),
+            ("req_valid ack & req_valid ! &", false),
+            ("(req_valid -> ack) & req_valid", true),
+        ];
+        let mut manager = Manager::new();
+        for (formula, expected) in tests {
+            let f = crate::parse(formula).unwrap();
+            let bdd = manager.build(&f);
+            assert_eq!(bdd != Bdd::FALSE, expected, "Test failed for {}", formula);
+            match manager.any_sat(bdd) {
+                Some(model) => assert!(f.eval(&model), "Model does not satisfy {}", formula),
+                None => assert!(!expected, "No model for {}", formula),
+            }
+        }
+    }
+
+    #[test]
+    fn sat_count() {
+        let tests = [("AB|", 3), ("AB&", 1), ("AA!&", 0), ("AB^C|", 6), ("AB>BC>&AC>|", 6), ("x1 x2 x3 x4 ||| x1! x2! x3! x4! |||&", 14)];
+        for (formula, expected) in tests {
+            // A manager of its own, so counts are over the formula's variables only
+            let mut manager = Manager::new();
+            let bdd = manager.build(&crate::parse(formula).unwrap());
+            assert_eq!(manager.sat_count(bdd), (expected as u64).into(), "Test failed for {}", formula);
+        }
+    }
+
+    #[test]
+    fn equivalent_formulas_are_one_node() {
+        let mut manager = Manager::new();
+        let f = manager.build(&crate::parse("AB>BC>&").unwrap());
+        let g = manager.build(&crate::parse("A!B|B!C|&").unwrap());
+        assert_eq!(f, g);
+    }
+
+    #[test]
+    fn restriction_and_quantification() {
+        let mut manager = Manager::new();
+        let f = manager.build(&crate::parse("AB>BC>&").unwrap());
+        let quantified = [
+            (manager.restrict(f, 'B'.into(), true), "C"),
+            (manager.restrict(f, 'B'.into(), false), "A!"),
+            (manager.exists(f, 'B'.into()), "AC>"),
+            (manager.forall(f, 'B'.into()), "A!C&"),
+        ];
+        for (result, expected) in quantified {
+            assert_eq!(result, manager.build(&crate::parse(expected).unwrap()), "Test failed for {}", expected);
+        }
+    }
+
+    #[test]
+    fn equality_chain() {
+        // x0 = x1 = ... = x59: 2 models among 2^60 assignments. Below x0 each
+        // variable has a node for "the rest are all 0" and one for "all 1",
+        // except x59 whose two nodes are one through a complemented edge
+        let mut manager = Manager::new();
+        let chain = (1..60).fold(String::from("1"), |acc, i| format!("{} x{} x{}=&", acc, i - 1, i));
+        let bdd = manager.build(&crate::parse(&chain).unwrap());
+        assert_eq!(manager.sat_count(bdd), 2u64.into());
+        assert_eq!(manager.size(bdd), 1 + 2 * 58 + 1 + 1);
+    }
+
+    // (a0 & b0) | ... | (a9 & b9): exponential with every a before every b,
+    // 2 nodes per pair plus the terminal with each a next to its b
+    fn pairs() -> Formula {
+        let pairs = (1..10).fold(String::from("a0 b0&"), |acc, i| format!("{} a{} b{}&|", acc, i, i));
+        crate::parse(&pairs).unwrap()
+    }
+
+    #[test]
+    fn sifting_finds_the_linear_order() {
+        let f = pairs();
+        let mut manager = Manager::new();
+        let mut roots = [manager.build(&f)];
+        assert_eq!(manager.size_of(&roots), 2047);
+        manager.sift(&mut roots);
+        assert_eq!(manager.size_of(&roots), 21);
+        assert_eq!(roots[0], manager.build(&f), "Sifting changed the function");
+    }
+
+    #[test]
+    fn static_order_of_first_appearance() {
+        let f = pairs();
+        let mut manager = Manager::new();
+        manager.add_vars(&static_order(&f));
+        let bdd = manager.build(&f);
+        assert_eq!(manager.size(bdd), 21);
+        assert_eq!(manager.order()[..4], ["a0".into(), "b0".into(), "a1".into(), "b1".into()]);
+    }
+
+    #[test]
+    fn dot() {
+        // A^B is the complement of A=B: complemented edges end in a hollow dot
+        let mut manager = Manager::new();
+        let bdd = manager.build(&crate::parse("AB^").unwrap());
+        let expected = "digraph bdd {
+  f [shape=none];
+  f -> n3 [arrowhead=odot];
+  n3 [label=\"A\"];
+  n3 -> n2 [style=dashed, arrowhead=odot];
+  n3 -> n2;
+  n2 [label=\"B\"];
+  n2 -> n0 [style=dashed, arrowhead=odot];
+  n2 -> n0;
+  n0 [shape=box, label=\"1\"];
+}
+";
+        assert_eq!(manager.to_dot(bdd), expected);
+    }
+}
@@ -0,0 +1,84 @@
+//! Canonical two-level forms read off the truth table: one term per row.
+//!
+//! Rows are numbered as in ex04's table, from the all-false row with the
+//! first variable (in name order) as the most significant bit, so for `A`
+//! and `B` minterm 2 is `A & !B`.
+
+use crate::cnf::{chain, Lit};
+use crate::formula::Formula;
+use crate::truth;
+use crate::var::Var;
+
+/// The rows of the truth table over `vars` where the formula is true.
+pub fn minterms(f: &Formula, vars: &[Var]) -> Vec<u64> {
+    truth::ones(&truth::table(f, vars)).collect()
+}
+
+/// The rows of the truth table over `vars` where the formula is false.
+pub fn maxterms(f: &Formula, vars: &[Var]) -> Vec<u64> {
+    truth::ones(&truth::table(&!f.clone(), vars)).collect()
+}
+
+/// The disjunction of one full conjunction per true row, Σm(...):
+/// `AB^` becomes `A!B&AB!&|`.
+///
+/// ```
+/// use boole::canonical::sum_of_minterms;
+///
+/// let f = boole::parse("AB>").unwrap();
+/// assert_eq!(sum_of_minterms(&f).to_string(), "A!B!&A!B&AB&||");
+/// ```
+pub fn sum_of_minterms(f: &Formula) -> Formula {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let terms = minterms(f, &vars).into_iter().map(|i| {
+        let lits = literals(&vars, i, true).map(Lit::to_formula);
+        chain(lits.collect(), Formula::and, true)
+    });
+    chain(terms.collect(), Formula::or, false)
+}
+
+/// The conjunction of one full disjunction per false row, ΠM(...), each
+/// ruling its row out: `AB^` becomes `AB|A!B!|&`.
+///
+/// ```
+/// use boole::canonical::product_of_maxterms;
+///
+/// let f = boole::parse("AB>").unwrap();
+/// assert_eq!(product_of_maxterms(&f).to_string(), "A!B|");
+/// ```
+pub fn product_of_maxterms(f: &Formula) -> Formula {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let clauses = maxterms(f, &vars).into_iter().map(|i| {
+        let lits = literals(&vars, i, false).map(Lit::to_formula);
+        chain(lits.collect(), Formula::or, false)
+    });
+    chain(clauses.collect(), Formula::and, true)
+}
+
+// The literals true in row `index`, or false in it if `truth` is false.
+fn literals(vars: &[Var], index: u64, truth: bool) -> impl Iterator<Item = Lit> + '_ {
+    let n = vars.len();
+    vars.iter().enumerate().map(move |(i, &var)| Lit { var, positive: ((index >> (n - 1 - i)) & 1 == 1) == truth })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_forms() {
+        let tests = [
+            ("AB^", vec![1, 2], "A!B&AB!&|", "AB|A!B!|&"),
+            ("AB&C|", vec![1, 3, 5, 6, 7], "A!B!C&&A!BC&&AB!C&&ABC!&&ABC&&||||", "ABC||AB!C||A!BC||&&"),
+            ("AA!&", vec![], "0", "AA!&"),
+            ("AA!|", vec![0, 1], "A!A|", "1"),
+        ];
+        for (input, rows, sum, product) in tests {
+            let f = crate::parse(input).unwrap();
+            let vars: Vec<Var> = f.vars().into_iter().collect();
+            assert_eq!(minterms(&f, &vars), rows, "Test failed for {}", input);
+            assert_eq!(sum_of_minterms(&f).to_string(), sum, "Test failed for {}", input);
+            assert_eq!(product_of_maxterms(&f).to_string(), product, "Test failed for {}", input);
+        }
+    }
+}
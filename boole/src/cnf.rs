@@ -0,0 +1,213 @@
+use std::collections::BTreeSet;
+use std::ops::Not;
+
+use crate::formula::Formula;
+use crate::nnf::to_nnf;
+use crate::tseitin;
+use crate::var::Var;
+
+/// A variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit {
+    pub var: Var,
+    pub positive: bool,
+}
+
+impl Lit {
+    pub fn pos(var: Var) -> Lit {
+        Lit { var, positive: true }
+    }
+
+    pub fn neg(var: Var) -> Lit {
+        Lit { var, positive: false }
+    }
+
+    pub fn to_formula(self) -> Formula {
+        if self.positive {
+            Formula::Var(self.var)
+        } else {
+            !Formula::Var(self.var)
+        }
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit { var: self.var, positive: !self.positive }
+    }
+}
+
+/// A disjunction of literals; the empty clause is `false`.
+pub type Clause = Vec<Lit>;
+
+/// How [`Cnf::encode`] turns a formula into clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Distribution, see [`Cnf::from_formula`]: equivalent to the formula,
+    /// but possibly exponentially larger.
+    Equivalent,
+    /// Equisatisfiable and linear in size, preserving model counts, see
+    /// [`tseitin::tseitin`].
+    Tseitin,
+    /// Equisatisfiable and linear in size with fewer clauses, see
+    /// [`tseitin::plaisted_greenbaum`].
+    PlaistedGreenbaum,
+}
+
+/// A conjunction of clauses; the empty conjunction is `true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cnf {
+    pub clauses: Vec<Clause>,
+}
+
+impl Cnf {
+    /// Converts a formula by distributing `|` over `&` on its negation normal
+    /// form, simplifying the clauses after every step: repeated literals are
+    /// dropped, clauses holding a literal and its negation are removed, and so
+    /// is any clause containing another one (subsumption).
+    pub fn from_formula(f: &Formula) -> Cnf {
+        Cnf { clauses: distribute(&to_nnf(f), true) }
+    }
+
+    /// Converts a formula with the given encoding.
+    ///
+    /// ```
+    /// use boole::cnf::{Cnf, Encoding};
+    ///
+    /// // Distribution doubles the clauses with every term, Tseitin adds a few
+    /// let f = boole::parse("AB&CD&|EF&|GH&|").unwrap();
+    /// assert_eq!(Cnf::encode(&f, Encoding::Equivalent).clauses.len(), 16);
+    /// assert_eq!(Cnf::encode(&f, Encoding::Tseitin).clauses.len(), 22);
+    /// assert_eq!(Cnf::encode(&f, Encoding::PlaistedGreenbaum).clauses.len(), 12);
+    /// ```
+    pub fn encode(f: &Formula, encoding: Encoding) -> Cnf {
+        match encoding {
+            Encoding::Equivalent => Cnf::from_formula(f),
+            Encoding::Tseitin => tseitin::tseitin(f),
+            Encoding::PlaistedGreenbaum => tseitin::plaisted_greenbaum(f),
+        }
+    }
+
+    /// The CNF as a formula, with every operator of a clause or conjunction at
+    /// the end as the subject expects: `A!B!C!||`.
+    pub fn to_formula(&self) -> Formula {
+        let clauses = self.clauses.iter().map(|clause| {
+            let lits = clause.iter().map(|lit| lit.to_formula());
+            chain(lits.collect(), Formula::or, false)
+        });
+        chain(clauses.collect(), Formula::and, true)
+    }
+
+    /// The variables used by the clauses, sorted by name.
+    pub fn vars(&self) -> BTreeSet<Var> {
+        self.clauses.iter().flatten().map(|lit| lit.var).collect()
+    }
+}
+
+/// Rewrites a formula into conjunctive normal form by distributing `|`
+/// over `&` on its negation normal form.
+///
+/// Clauses and conjunctions are printed with every operator at the end,
+/// as the subject expects: `AB&!C!|` becomes `A!B!C!||`.
+pub fn to_cnf(f: &Formula) -> Formula {
+    Cnf::from_formula(f).to_formula()
+}
+
+/// Tells whether a formula is a conjunction of disjunctions of literals,
+/// where a literal is a variable, a negated variable or a constant.
+///
+/// ```
+/// use boole::cnf::{is_cnf, to_cnf};
+///
+/// let f = boole::parse("AB&C|").unwrap();
+/// assert!(!is_cnf(&f));
+/// assert!(is_cnf(&to_cnf(&f)));
+/// ```
+pub fn is_cnf(f: &Formula) -> bool {
+    match f {
+        Formula::And(a, b) => is_cnf(a) && is_cnf(b),
+        _ => is_clause(f),
+    }
+}
+
+fn is_clause(f: &Formula) -> bool {
+    match f {
+        Formula::Or(a, b) => is_clause(a) && is_clause(b),
+        Formula::Not(a) => matches!(**a, Formula::Var(_)),
+        Formula::Const(_) | Formula::Var(_) => true,
+        _ => false,
+    }
+}
+
+// The clauses of a formula in NNF, or its terms if `clauses` is false: the
+// two are the same construction with `&` and `|` swapped. The operator
+// joining the sets concatenates them, the one inside a set distributes over
+// it. Each step is simplified, not only the end result: over n variables at
+// most 3^n distinct sets survive, where the raw products of nested `^` and
+// `=` grow exponentially with the formula.
+pub(crate) fn distribute(f: &Formula, clauses: bool) -> Vec<Vec<Lit>> {
+    match f {
+        // `true` is no clause but one empty term, `false` the other way round
+        Formula::Const(value) if *value == clauses => vec![],
+        Formula::Const(_) => vec![vec![]],
+        Formula::Var(var) => vec![vec![Lit::pos(*var)]],
+        Formula::Not(a) => match **a {
+            Formula::Var(var) => vec![vec![Lit::neg(var)]],
+            _ => unreachable!("not in negation normal form"),
+        },
+        Formula::And(a, b) | Formula::Or(a, b) => {
+            let (left, right) = (distribute(a, clauses), distribute(b, clauses));
+            if matches!(f, Formula::And(..)) == clauses {
+                simplify(left.into_iter().chain(right).collect())
+            } else {
+                let product = left.iter().flat_map(|l| right.iter().map(move |r| l.iter().chain(r).copied().collect()));
+                simplify(product.collect())
+            }
+        }
+        _ => unreachable!("not in negation normal form"),
+    }
+}
+
+// Drops repeated literals, clauses with a literal and its negation, and
+// clauses containing another clause. The same rules simplify DNF terms.
+pub(crate) fn simplify(clauses: Vec<Clause>) -> Vec<Clause> {
+    let mut sets: Vec<(BTreeSet<Lit>, Clause)> = Vec::new();
+    for clause in clauses {
+        let mut set = BTreeSet::new();
+        let mut deduped = Vec::new();
+        for lit in clause {
+            if set.insert(lit) {
+                deduped.push(lit);
+            }
+        }
+        if !set.iter().any(|&lit| set.contains(&!lit)) {
+            sets.push((set, deduped));
+        }
+    }
+
+    // A clause is dropped if a shorter one, or an identical earlier one, is
+    // contained in it. Checking shortest first, kept clauses only need to be
+    // compared with the clauses kept before them.
+    let mut order: Vec<usize> = (0..sets.len()).collect();
+    order.sort_by_key(|&i| sets[i].0.len());
+    let mut kept: Vec<usize> = Vec::new();
+    let mut keep = vec![false; sets.len()];
+    for i in order {
+        if !kept.iter().any(|&k| sets[k].0.is_subset(&sets[i].0)) {
+            kept.push(i);
+            keep[i] = true;
+        }
+    }
+    sets.into_iter().zip(keep).filter_map(|((_, clause), keep)| keep.then_some(clause)).collect()
+}
+
+// Folds `items` into a right-nested chain, so `ABC` becomes `A(BC)`, i.e. `ABC||`.
+pub(crate) fn chain(items: Vec<Formula>, op: fn(Formula, Formula) -> Formula, empty: bool) -> Formula {
+    let mut items = items.into_iter().rev();
+    match items.next() {
+        Some(last) => items.fold(last, |acc, item| op(item, acc)),
+        None => Formula::Const(empty),
+    }
+}
@@ -0,0 +1,30 @@
+/// Maps a point to `[0, 1]` along the Z-order curve by interleaving the bits
+/// of `x` (even positions) and `y` (odd positions).
+pub fn map(x: u16, y: u16) -> f64 {
+    let mut z: u32 = 0;
+    for i in 0..16 {
+        z |= ((x as u32 >> i) & 1) << (2 * i); // x bit to even positions
+        z |= ((y as u32 >> i) & 1) << (2 * i + 1); // y bit to odd positions
+    }
+
+    // Divide by 2^32 - 1 so the largest value maps to exactly 1.0
+    (z as f64) / 4294967295.0
+}
+
+/// Inverse of [`map`]. Returns `None` when `n` is outside `[0, 1]`.
+pub fn reverse_map(n: f64) -> Option<(u16, u16)> {
+    if !(0.0..=1.0).contains(&n) {
+        return None;
+    }
+
+    let z = (n * 4294967295.0).round() as u32;
+
+    // De-interleave bits to reconstruct x and y
+    let mut x: u16 = 0;
+    let mut y: u16 = 0;
+    for i in 0..16 {
+        x |= (((z >> (2 * i)) & 1) as u16) << i; // Even bits to x
+        y |= (((z >> (2 * i + 1)) & 1) as u16) << i; // Odd bits to y
+    }
+    Some((x, y))
+}
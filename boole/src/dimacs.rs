@@ -0,0 +1,301 @@
+//! DIMACS CNF, the input format of standard SAT solvers and benchmark suites
+//! such as SATLIB:
+//!
+//! ```text
+//! c comment
+//! p cnf 3 2
+//! 1 -3 0
+//! 2 3 -1 0
+//! ```
+//!
+//! Variable `n` maps to the [`Var`] `xn`. Other names survive a round trip
+//! through `c var <n> <name>` comments, which [`write`] emits and [`parse`] reads.
+
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+
+use crate::cnf::{Clause, Cnf, Lit};
+use crate::sat::SatResult;
+use crate::var::{self, Var};
+
+/// Why a DIMACS file could not be read. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DimacsError {
+    /// A clause before the `p cnf` line, or no `p cnf` line at all.
+    MissingHeader { line: usize },
+    /// A `p` line that is not `p cnf <variables> <clauses>`, or a second one.
+    BadHeader { line: usize },
+    /// A token that is not an integer.
+    BadLiteral { line: usize, token: String },
+    /// A literal whose variable exceeds the count declared in the header.
+    VarOutOfRange { line: usize, var: u64 },
+    /// A malformed `c var <n> <name>` comment.
+    BadVarName { line: usize },
+    /// The last clause has no terminating `0`.
+    UnterminatedClause,
+    /// The number of clauses differs from the header.
+    ClauseCountMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimacsError::MissingHeader { line } => write!(f, "line {}: expected 'p cnf' header first", line),
+            DimacsError::BadHeader { line } => {
+                write!(f, "line {}: expected a single 'p cnf <variables> <clauses>' line", line)
+            }
+            DimacsError::BadLiteral { line, token } => write!(f, "line {}: '{}' is not a literal", line, token),
+            DimacsError::VarOutOfRange { line, var } => {
+                write!(f, "line {}: variable {} exceeds the count in the header", line, var)
+            }
+            DimacsError::BadVarName { line } => {
+                write!(f, "line {}: expected 'c var <number> <name>'", line)
+            }
+            DimacsError::UnterminatedClause => write!(f, "last clause is not terminated by 0"),
+            DimacsError::ClauseCountMismatch { expected, found } => {
+                write!(f, "header declares {} clauses but {} were found", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for DimacsError {}
+
+/// Reads a DIMACS CNF file. Clauses may span several lines, and a `%` line
+/// (as in SATLIB files) ends the clause list.
+pub fn parse(input: &str) -> Result<Cnf, DimacsError> {
+    let mut header: Option<(u64, usize)> = None;
+    let mut names: HashMap<u64, Var> = HashMap::new();
+    let mut clauses: Vec<Clause> = Vec::new();
+    let mut clause: Clause = Vec::new();
+    let mut literals: Vec<i64> = Vec::new();
+
+    for (n, text) in input.lines().enumerate() {
+        let line = n + 1;
+        let text = text.trim();
+
+        if let Some(rest) = text.strip_prefix("c var ") {
+            let (number, name) = parse_var_name(rest).ok_or(DimacsError::BadVarName { line })?;
+            names.insert(number, name);
+            continue;
+        }
+        if text.is_empty() || text.starts_with('c') {
+            continue;
+        }
+        if text.starts_with('%') {
+            break;
+        }
+        if text.starts_with('p') {
+            if header.is_some() {
+                return Err(DimacsError::BadHeader { line });
+            }
+            header = Some(parse_header(text).ok_or(DimacsError::BadHeader { line })?);
+            continue;
+        }
+
+        let (num_vars, _) = header.ok_or(DimacsError::MissingHeader { line })?;
+        for token in text.split_whitespace() {
+            let lit: i64 = token
+                .parse()
+                .map_err(|_| DimacsError::BadLiteral { line, token: token.to_string() })?;
+            if lit.unsigned_abs() > num_vars {
+                return Err(DimacsError::VarOutOfRange { line, var: lit.unsigned_abs() });
+            }
+            literals.push(lit);
+        }
+    }
+
+    let (_, num_clauses) = header.ok_or(DimacsError::MissingHeader { line: input.lines().count() + 1 })?;
+
+    // Names are resolved last since `c var` comments may come after the clauses.
+    for lit in literals {
+        if lit == 0 {
+            clauses.push(std::mem::take(&mut clause));
+            continue;
+        }
+        let number = lit.unsigned_abs();
+        let var = *names.entry(number).or_insert_with(|| Var::new(&format!("x{}", number)));
+        clause.push(Lit { var, positive: lit > 0 });
+    }
+
+    if !clause.is_empty() {
+        return Err(DimacsError::UnterminatedClause);
+    }
+    if clauses.len() != num_clauses {
+        return Err(DimacsError::ClauseCountMismatch { expected: num_clauses, found: clauses.len() });
+    }
+    Ok(Cnf { clauses })
+}
+
+/// Writes clauses as DIMACS CNF.
+///
+/// Variables named `x1`, `x2`, ... keep their numbers. Otherwise variables
+/// are numbered in name order and each number's name is recorded in a
+/// `c var` comment.
+pub fn write(cnf: &Cnf) -> String {
+    let numbers = numbering(cnf.vars());
+    let num_vars = numbers.values().copied().max().unwrap_or(0);
+    let mut out = names(&numbers);
+    out += &format!("p cnf {} {}\n", num_vars, cnf.clauses.len());
+    for clause in &cnf.clauses {
+        for lit in clause {
+            let n = numbers[&lit.var] as i64;
+            out += &format!("{} ", if lit.positive { n } else { -n });
+        }
+        out += "0\n";
+    }
+    out
+}
+
+/// Writes a solver result the way SAT competition solvers report it: an
+/// `s` status line and, when satisfiable, `v` lines listing every variable's
+/// literal and ending with `0`.
+///
+/// ```text
+/// s SATISFIABLE
+/// v 1 -2 3 0
+/// ```
+///
+/// Variables are numbered as [`write`] numbers the variables of the model,
+/// with the same `c var` comments.
+pub fn write_result(result: &SatResult) -> String {
+    let model = match result {
+        SatResult::Sat(model) => model,
+        SatResult::Unsat => return "s UNSATISFIABLE\n".to_string(),
+    };
+
+    let numbers = numbering(model.keys().copied().collect());
+    let mut lits: Vec<i64> = model
+        .iter()
+        .map(|(var, &value)| {
+            let n = numbers[var] as i64;
+            if value { n } else { -n }
+        })
+        .collect();
+    lits.sort_by_key(|lit| lit.abs());
+    lits.push(0);
+
+    let mut out = names(&numbers);
+    out += "s SATISFIABLE\n";
+    let mut line = String::from("v");
+    for lit in lits {
+        let token = format!(" {}", lit);
+        if line.len() + token.len() > 78 {
+            out += &line;
+            out += "\n";
+            line = String::from("v");
+        }
+        line += &token;
+    }
+    out += &line;
+    out += "\n";
+    out
+}
+
+// The `c var` comments for variables not named after their number.
+fn names(numbers: &HashMap<Var, usize>) -> String {
+    let mut named: Vec<(Var, usize)> = numbers
+        .iter()
+        .filter(|&(&var, &n)| dimacs_number(var) != Some(n))
+        .map(|(&var, &n)| (var, n))
+        .collect();
+    named.sort_by_key(|&(_, n)| n);
+    named.iter().map(|(var, n)| format!("c var {} {}\n", n, var.token())).collect()
+}
+
+fn numbering(vars: BTreeSet<Var>) -> HashMap<Var, usize> {
+    if vars.iter().all(|&var| dimacs_number(var).is_some()) {
+        vars.into_iter().map(|var| (var, dimacs_number(var).unwrap())).collect()
+    } else {
+        vars.into_iter().zip(1..).collect()
+    }
+}
+
+// The number of a variable named `x<n>`, as created by `parse`.
+fn dimacs_number(var: Var) -> Option<usize> {
+    let digits = var.name().strip_prefix('x')?;
+    if digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn parse_header(text: &str) -> Option<(u64, usize)> {
+    let mut fields = text.split_whitespace();
+    if fields.next() != Some("p") || fields.next() != Some("cnf") {
+        return None;
+    }
+    let num_vars = fields.next()?.parse().ok()?;
+    let num_clauses = fields.next()?.parse().ok()?;
+    fields.next().is_none().then_some((num_vars, num_clauses))
+}
+
+// Parses `<n> <name>` from a `c var` comment; the name may be quoted.
+fn parse_var_name(text: &str) -> Option<(u64, Var)> {
+    let (number, name) = text.trim().split_once(' ')?;
+    let name = name.trim();
+    let (var, len) = var::scan(name, 0)?.ok()?;
+    (len == name.len()).then_some((number.parse().ok()?, var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sat;
+
+    #[test]
+    fn parse_satlib_layout() {
+        // Comments, clauses over several lines, `%` trailer
+        let cnf = parse("c satisfiable\np cnf 3 3\n1 -2\n 0 2 3 0\n-1 -3 0\n%\n0\n").unwrap();
+        assert_eq!(cnf.clauses.len(), 3);
+        assert!(matches!(sat::solve_cnf(&cnf), SatResult::Sat(_)));
+        let cnf = parse("c x1 and not x1\np cnf 1 2\n1 0\n-1 0\n").unwrap();
+        assert_eq!(sat::solve_cnf(&cnf), SatResult::Unsat);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let errors = [
+            ("1 0\n", DimacsError::MissingHeader { line: 1 }),
+            ("p cnf 2\n", DimacsError::BadHeader { line: 1 }),
+            ("p cnf 2 1\n1 3 0\n", DimacsError::VarOutOfRange { line: 2, var: 3 }),
+            ("p cnf 2 1\n1 -2\n", DimacsError::UnterminatedClause),
+            ("p cnf 2 2\n1 -2 0\n", DimacsError::ClauseCountMismatch { expected: 2, found: 1 }),
+        ];
+        for (input, expected) in errors {
+            assert_eq!(parse(input), Err(expected), "Test failed for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn write_round_trip() {
+        for (input, expected) in [
+            ("AB|C&", "c var 1 A\nc var 2 B\nc var 3 C\np cnf 3 2\n1 2 0\n3 0\n"),
+            ("x1 x3! |", "p cnf 3 1\n1 -3 0\n"),
+            ("1", "p cnf 0 0\n"),
+            ("0", "p cnf 0 1\n0\n"),
+        ] {
+            let cnf = Cnf::from_formula(&crate::parse(input).unwrap());
+            let result = write(&cnf);
+            assert_eq!(result, expected, "Test failed for {}", input);
+            assert_eq!(parse(&result), Ok(cnf), "Round trip failed for {}", input);
+        }
+    }
+
+    #[test]
+    fn write_result_as_solvers_print_it() {
+        for (formula, expected) in [
+            ("AB&C!&", "c var 1 A\nc var 2 B\nc var 3 C\ns SATISFIABLE\nv 1 2 -3 0\n"),
+            ("x2 x1! &", "s SATISFIABLE\nv -1 2 0\n"),
+            ("AA!&", "s UNSATISFIABLE\n"),
+        ] {
+            let f = crate::parse(formula).unwrap();
+            let result = sat::solve(&f);
+            if let SatResult::Sat(model) = &result {
+                assert!(f.eval(model), "Model does not satisfy {}", formula);
+            }
+            assert_eq!(write_result(&result), expected, "Test failed for {}", formula);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::cnf::{chain, distribute, Lit};
+use crate::formula::Formula;
+use crate::nnf::to_nnf;
+
+/// A conjunction of literals; the empty term is `true`.
+pub type Term = Vec<Lit>;
+
+/// A disjunction of terms; the empty disjunction is `false`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dnf {
+    pub terms: Vec<Term>,
+}
+
+impl Dnf {
+    /// Converts a formula by distributing `&` over `|` on its negation normal
+    /// form, simplifying the terms after every step: repeated literals are
+    /// dropped, terms holding a literal and its negation are removed, and so
+    /// is any term containing another one (absorption).
+    pub fn from_formula(f: &Formula) -> Dnf {
+        Dnf { terms: distribute(&to_nnf(f), false) }
+    }
+
+    /// The DNF as a formula, with every operator of a term or disjunction at
+    /// the end: `A!B!C!&&`.
+    pub fn to_formula(&self) -> Formula {
+        let terms = self.terms.iter().map(|term| {
+            let lits = term.iter().map(|lit| lit.to_formula());
+            chain(lits.collect(), Formula::and, true)
+        });
+        chain(terms.collect(), Formula::or, false)
+    }
+}
+
+/// Rewrites a formula into disjunctive normal form, the dual of
+/// [`to_cnf`](crate::cnf::to_cnf): `AB|C&` becomes `AC&BC&|`.
+pub fn to_dnf(f: &Formula) -> Formula {
+    Dnf::from_formula(f).to_formula()
+}
+
+/// Tells whether a formula is a disjunction of conjunctions of literals,
+/// where a literal is a variable, a negated variable or a constant.
+pub fn is_dnf(f: &Formula) -> bool {
+    match f {
+        Formula::Or(a, b) => is_dnf(a) && is_dnf(b),
+        _ => is_term(f),
+    }
+}
+
+fn is_term(f: &Formula) -> bool {
+    match f {
+        Formula::And(a, b) => is_term(a) && is_term(b),
+        Formula::Not(a) => matches!(**a, Formula::Var(_)),
+        Formula::Const(_) | Formula::Var(_) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a formula string could not be parsed.
+///
+/// Offsets are byte offsets into the parsed string; `depth` is the number of
+/// operands on the stack when the error was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character that is neither an operand nor an operator.
+    UnknownSymbol { symbol: char, offset: usize },
+    /// An operator with fewer operands on the stack than it needs.
+    OperandUnderflow { op: char, offset: usize, depth: usize },
+    /// More than one operand left at the end; `offset` is where the first
+    /// unconsumed one starts.
+    LeftoverOperands { offset: usize, depth: usize },
+    /// Nothing but whitespace.
+    EmptyInput,
+    /// A `"` that does not start a non-empty, closed variable name.
+    BadQuotedName { offset: usize },
+    /// Infix only: an operator, `)` or the end of input where an operand should be.
+    ExpectedOperand { offset: usize },
+    /// Infix only: an operand or `(` where an operator or the end should be.
+    ExpectedOperator { offset: usize },
+    /// Infix only: a `(` that is never closed; `offset` is the `(`.
+    UnclosedParen { offset: usize },
+}
+
+impl ParseError {
+    /// Byte offset of the offending character.
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::UnknownSymbol { offset, .. }
+            | ParseError::OperandUnderflow { offset, .. }
+            | ParseError::LeftoverOperands { offset, .. }
+            | ParseError::BadQuotedName { offset }
+            | ParseError::ExpectedOperand { offset }
+            | ParseError::ExpectedOperator { offset }
+            | ParseError::UnclosedParen { offset } => *offset,
+            ParseError::EmptyInput => 0,
+        }
+    }
+
+    /// Formats the error with the offending line of `source` and a caret
+    /// under the offending character:
+    ///
+    /// ```text
+    /// error: '&' needs 2 operands but the stack holds 1
+    ///  --> 1:2
+    ///   |
+    ///   | A&B
+    ///   |  ^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let offset = self.offset().min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..].find('\n').map_or(source.len(), |i| offset + i);
+        let line_number = source[..offset].matches('\n').count() + 1;
+        let column = source[line_start..offset].chars().count();
+
+        format!(
+            "error: {}\n --> {}:{}\n  |\n  | {}\n  | {}^",
+            self,
+            line_number,
+            column + 1,
+            &source[line_start..line_end],
+            " ".repeat(column)
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnknownSymbol { symbol, .. } => write!(f, "unknown symbol '{}'", symbol),
+            ParseError::OperandUnderflow { op, depth, .. } => {
+                let needed = if *op == '!' { 1 } else { 2 };
+                let plural = if needed == 1 { "" } else { "s" };
+                write!(f, "'{}' needs {} operand{} but the stack holds {}", op, needed, plural, depth)
+            }
+            ParseError::LeftoverOperands { depth, .. } => {
+                write!(f, "{} operands left on the stack, expected 1", depth)
+            }
+            ParseError::EmptyInput => write!(f, "empty formula"),
+            ParseError::BadQuotedName { .. } => write!(f, "quoted variable name is empty or not closed"),
+            ParseError::ExpectedOperand { .. } => write!(f, "expected an operand"),
+            ParseError::ExpectedOperator { .. } => write!(f, "expected an operator"),
+            ParseError::UnclosedParen { .. } => write!(f, "unclosed '('"),
+        }
+    }
+}
+
+impl Error for ParseError {}
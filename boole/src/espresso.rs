@@ -0,0 +1,366 @@
+//! Heuristic two-level minimization in the style of Berkeley Espresso.
+//!
+//! Quine–McCluskey lists every prime implicant, which stops being feasible
+//! past a dozen or so variables. Espresso instead improves a cover in place:
+//!
+//! - EXPAND grows each cube as far as the off-set allows, making it prime,
+//!   and drops the cubes it now contains.
+//! - IRREDUNDANT removes cubes covered by the others.
+//! - REDUCE shrinks each cube to the part no other cube covers, so the next
+//!   EXPAND can grow it in a different direction.
+//!
+//! The loop stops when a round no longer lowers the number of cubes and
+//! literals. The result is prime and irredundant, usually minimal but not
+//! guaranteed to be.
+//!
+//! Functions may have several outputs sharing cubes, as in a PLA: each cube
+//! has an input part and the set of outputs it drives.
+
+use crate::cnf::{chain, Lit};
+use crate::dnf::Dnf;
+use crate::formula::Formula;
+use crate::var::Var;
+
+/// A product term of a multi-output function.
+///
+/// Bit `i` of `zeros` is set if input `i` may be 0, and of `ones` if it may
+/// be 1, so an input absent from the term has both bits set. Bit `j` of
+/// `outputs` is set if the term belongs to output `j`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cube {
+    pub zeros: u64,
+    pub ones: u64,
+    pub outputs: u64,
+}
+
+impl Cube {
+    /// The cube of all input values, driving `outputs`.
+    pub fn universe(inputs: usize, outputs: u64) -> Cube {
+        Cube { zeros: full(inputs), ones: full(inputs), outputs }
+    }
+
+    /// The number of inputs fixed by the term.
+    pub fn literals(self) -> usize {
+        (self.zeros ^ self.ones).count_ones() as usize
+    }
+
+    /// Whether every input value of `other` is also one of `self`.
+    pub fn contains(self, other: Cube) -> bool {
+        other.zeros & !self.zeros == 0 && other.ones & !self.ones == 0
+    }
+
+    /// Whether some input values belong to both cubes.
+    pub fn intersects(self, other: Cube, inputs: usize) -> bool {
+        (self.zeros & other.zeros) | (self.ones & other.ones) == full(inputs)
+    }
+
+    /// The input part written one character per input, as in a PLA file.
+    pub fn pattern(self, inputs: usize) -> String {
+        (0..inputs)
+            .map(|i| match (self.zeros >> i & 1, self.ones >> i & 1) {
+                (1, 1) => '-',
+                (0, 1) => '1',
+                (1, 0) => '0',
+                _ => '~',
+            })
+            .collect()
+    }
+
+    fn raise(self, input: usize) -> Cube {
+        Cube { zeros: self.zeros | 1 << input, ones: self.ones | 1 << input, outputs: self.outputs }
+    }
+}
+
+fn full(inputs: usize) -> u64 {
+    if inputs == 64 {
+        u64::MAX
+    } else {
+        (1 << inputs) - 1
+    }
+}
+
+/// Minimizes the multi-output function whose on-set is `on`, with `dc` as
+/// don't-care set, over `inputs` inputs and `outputs` outputs (at most 64
+/// of each).
+pub fn espresso(on: &[Cube], dc: &[Cube], inputs: usize, outputs: usize) -> Vec<Cube> {
+    let all: Vec<Cube> = on.iter().chain(dc).copied().collect();
+    let care = (0..outputs).map(|j| output_cover(&all, j)).collect();
+    let space = Space { inputs, outputs, care };
+    let mut cover: Vec<Cube> = on.iter().copied().filter(|cube| cube.outputs != 0).collect();
+
+    cover = space.expand(cover);
+    cover = space.irredundant(cover, dc);
+    let mut best = (cost(&cover), cover.clone());
+    loop {
+        cover = space.reduce(cover, dc);
+        cover = space.expand(cover);
+        cover = space.irredundant(cover, dc);
+        if cost(&cover) >= best.0 {
+            break;
+        }
+        best = (cost(&cover), cover.clone());
+    }
+
+    let mut cover = best.1;
+    // In the order of the patterns, `0` before `1` before `-`
+    cover.sort_by_key(|cube| (cube.pattern(inputs).replace('-', "2"), cube.outputs));
+    cover
+}
+
+/// A small sum of products equivalent to the formula, found with Espresso
+/// from the cubes of its disjunctive normal form, so no truth table is
+/// built. The formula may have up to 64 variables, but its DNF must be small
+/// enough to build: it is exponential in the formula for some functions, 2^(n-1)
+/// terms for the parity (`^` chain) of n variables.
+///
+/// ```
+/// let f = boole::parse("AB&AB!&|C&").unwrap();
+/// assert_eq!(boole::espresso::minimize(&f).to_string(), "AC&");
+/// ```
+pub fn minimize(f: &Formula) -> Formula {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    assert!(vars.len() <= 64, "espresso handles at most 64 variables");
+    let on: Vec<Cube> = Dnf::from_formula(f)
+        .terms
+        .iter()
+        .map(|term| {
+            let mut cube = Cube::universe(vars.len(), 1);
+            for lit in term {
+                let i = vars.binary_search(&lit.var).unwrap();
+                if lit.positive {
+                    cube.zeros &= !(1 << i);
+                } else {
+                    cube.ones &= !(1 << i);
+                }
+            }
+            cube
+        })
+        .collect();
+
+    let terms = espresso(&on, &[], vars.len(), 1).into_iter().map(|cube| {
+        let lits = vars.iter().enumerate().filter_map(|(i, &var)| match (cube.zeros >> i & 1, cube.ones >> i & 1) {
+            (0, 1) => Some(Lit::pos(var).to_formula()),
+            (1, 0) => Some(Lit::neg(var).to_formula()),
+            _ => None,
+        });
+        chain(lits.collect(), Formula::and, true)
+    });
+    chain(terms.collect(), Formula::or, false)
+}
+
+fn cost(cover: &[Cube]) -> (usize, usize) {
+    (cover.len(), cover.iter().map(|cube| cube.literals()).sum())
+}
+
+// The input parts of the cubes driving output `j`.
+fn output_cover<'a>(cubes: impl IntoIterator<Item = &'a Cube>, j: usize) -> Vec<Cube> {
+    cubes.into_iter().filter(|cube| cube.outputs >> j & 1 == 1).map(|cube| Cube { outputs: 1, ..*cube }).collect()
+}
+
+// Covers handled below are single-output: only their input parts matter.
+struct Space {
+    inputs: usize,
+    outputs: usize,
+    // For each output, its on-set and don't-care set: what cubes may grow into
+    care: Vec<Vec<Cube>>,
+}
+
+impl Space {
+    fn expand(&self, mut cover: Vec<Cube>) -> Vec<Cube> {
+        // Largest cubes first: they are the likeliest to swallow others
+        cover.sort_by_key(|cube| cube.literals());
+        let valid = |cube: Cube| (0..self.outputs).all(|j| cube.outputs >> j & 1 == 0 || self.covered(&self.care[j], cube));
+
+        let mut expanded: Vec<Cube> = Vec::new();
+        for (n, &cube) in cover.iter().enumerate() {
+            if expanded.iter().any(|big| big.contains(cube) && cube.outputs & !big.outputs == 0) {
+                continue;
+            }
+            // Grow towards the cubes still to come, so they are covered and
+            // dropped, then raise whatever inputs are left
+            let mut cube = cube;
+            for &other in &cover[n + 1..] {
+                let grown = Cube { zeros: cube.zeros | other.zeros, ones: cube.ones | other.ones, outputs: cube.outputs | other.outputs };
+                if grown != cube && valid(grown) {
+                    cube = grown;
+                }
+            }
+            for i in 0..self.inputs {
+                let raised = cube.raise(i);
+                if raised != cube && valid(raised) {
+                    cube = raised;
+                }
+            }
+            for j in 0..self.outputs {
+                let raised = Cube { outputs: cube.outputs | 1 << j, ..cube };
+                if raised != cube && valid(raised) {
+                    cube = raised;
+                }
+            }
+            expanded.retain(|small| !(cube.contains(*small) && small.outputs & !cube.outputs == 0));
+            expanded.push(cube);
+        }
+        expanded
+    }
+
+    fn irredundant(&self, mut cover: Vec<Cube>, dc: &[Cube]) -> Vec<Cube> {
+        // Smallest cubes first: they are the likeliest to be redundant
+        cover.sort_by_key(|cube| std::cmp::Reverse(cube.literals()));
+        let mut i = 0;
+        while i < cover.len() {
+            let cube = cover[i];
+            let others: Vec<Cube> = cover.iter().enumerate().filter(|&(k, _)| k != i).map(|(_, &other)| other).collect();
+            for j in 0..self.outputs {
+                if cube.outputs >> j & 1 == 1 && self.covered(&output_cover(others.iter().chain(dc), j), cube) {
+                    cover[i].outputs &= !(1 << j);
+                }
+            }
+            if cover[i].outputs == 0 {
+                cover.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        cover
+    }
+
+    fn reduce(&self, mut cover: Vec<Cube>, dc: &[Cube]) -> Vec<Cube> {
+        cover.sort_by_key(|cube| cube.literals());
+        let mut i = 0;
+        while i < cover.len() {
+            let cube = cover[i];
+            let others: Vec<Cube> = cover.iter().enumerate().filter(|&(k, _)| k != i).map(|(_, &other)| other).collect();
+
+            // The smallest cube containing what only `cube` covers
+            let mut reduced = Cube { zeros: 0, ones: 0, outputs: 0 };
+            for j in 0..self.outputs {
+                if cube.outputs >> j & 1 == 0 {
+                    continue;
+                }
+                let others = output_cover(others.iter().chain(dc), j);
+                if let Some(part) = self.complement_supercube(&self.cofactor(&others, cube)) {
+                    reduced.zeros |= part.zeros & cube.zeros;
+                    reduced.ones |= part.ones & cube.ones;
+                    reduced.outputs |= 1 << j;
+                }
+            }
+            if reduced.outputs == 0 {
+                cover.remove(i);
+            } else {
+                cover[i] = reduced;
+                i += 1;
+            }
+        }
+        cover
+    }
+
+    // Whether `cover` contains every input value of `cube`.
+    fn covered(&self, cover: &[Cube], cube: Cube) -> bool {
+        self.tautology(&self.cofactor(cover, cube))
+    }
+
+    // The cubes of `cover` meeting `cube`, with the inputs `cube` fixes made free.
+    fn cofactor(&self, cover: &[Cube], cube: Cube) -> Vec<Cube> {
+        let fixed = cube.zeros ^ cube.ones;
+        cover
+            .iter()
+            .filter(|other| other.intersects(cube, self.inputs))
+            .map(|other| Cube { zeros: other.zeros | fixed, ones: other.ones | fixed, outputs: other.outputs })
+            .collect()
+    }
+
+    // Inputs fixed to 0 by some cube, and inputs fixed to 1 by some cube.
+    fn polarities(&self, cover: &[Cube]) -> (u64, u64) {
+        let universe = full(self.inputs);
+        cover.iter().fold((0, 0), |(zeros, ones), cube| (zeros | (universe & !cube.ones), ones | (universe & !cube.zeros)))
+    }
+
+    // The input appearing in both polarities in the most cubes, or failing
+    // that in the most cubes at all.
+    fn split_input(&self, cover: &[Cube]) -> usize {
+        let (zeros, ones) = self.polarities(cover);
+        let binate = zeros & ones;
+        let count = |i: usize| cover.iter().filter(|cube| (cube.zeros ^ cube.ones) >> i & 1 == 1).count();
+        (0..self.inputs)
+            .filter(|&i| (zeros | ones) >> i & 1 == 1)
+            .max_by_key(|&i| (binate >> i & 1, count(i), std::cmp::Reverse(i)))
+            .unwrap()
+    }
+
+    // Whether a cover contains every input value, by Shannon expansion.
+    fn tautology(&self, cover: &[Cube]) -> bool {
+        let universe = full(self.inputs);
+        if cover.iter().any(|cube| cube.zeros & cube.ones == universe) {
+            return true;
+        }
+        if cover.is_empty() {
+            return false;
+        }
+        let (zeros, ones) = self.polarities(cover);
+        let unate = (zeros ^ ones) & universe;
+        if unate != 0 {
+            // A cube fixing an input that only ever appears in one polarity
+            // cannot help cover the other half: drop those cubes
+            let rest: Vec<Cube> = cover.iter().copied().filter(|cube| (cube.zeros ^ cube.ones) & unate == 0).collect();
+            return self.tautology(&rest);
+        }
+        let (low, high) = self.halves(self.split_input(cover));
+        self.tautology(&self.cofactor(cover, low)) && self.tautology(&self.cofactor(cover, high))
+    }
+
+    // The smallest cube containing the complement of a cover, `None` if
+    // the complement is empty.
+    fn complement_supercube(&self, cover: &[Cube]) -> Option<Cube> {
+        let universe = Cube::universe(self.inputs, 1);
+        if cover.iter().any(|cube| cube.zeros & cube.ones == universe.zeros) {
+            return None;
+        }
+        match cover {
+            [] => return Some(universe),
+            // The complement of a literal is the opposite literal; of a
+            // longer term, terms each flipping one input, spanning everything
+            [cube] if cube.literals() > 1 => return Some(universe),
+            [cube] => return Some(Cube { zeros: cube.ones, ones: cube.zeros, outputs: 1 }),
+            _ => {}
+        }
+
+        let (low, high) = self.halves(self.split_input(cover));
+        let parts = [low, high].map(|half| {
+            let part = self.complement_supercube(&self.cofactor(cover, half))?;
+            Some(Cube { zeros: part.zeros & half.zeros, ones: part.ones & half.ones, outputs: 1 })
+        });
+        match parts {
+            [Some(a), Some(b)] => Some(Cube { zeros: a.zeros | b.zeros, ones: a.ones | b.ones, outputs: 1 }),
+            [Some(part), None] | [None, Some(part)] => Some(part),
+            [None, None] => None,
+        }
+    }
+
+    // The halves of the space where input `i` is 0 and 1.
+    fn halves(&self, i: usize) -> (Cube, Cube) {
+        let universe = Cube::universe(self.inputs, 1);
+        (Cube { ones: universe.ones & !(1 << i), ..universe }, Cube { zeros: universe.zeros & !(1 << i), ..universe })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_results_as_quine_mccluskey() {
+        for (input, expected) in [("AB&AB!&|C&", "AC&"), ("ABC&&A!BC&&|AB!C&&|", "AC&BC&|"), ("AB=", "A!B!&AB&|")] {
+            assert_eq!(minimize(&crate::parse(input).unwrap()).to_string(), expected, "Test failed for {}", input);
+        }
+    }
+
+    #[test]
+    fn forty_variables() {
+        // Far beyond a truth table: x0 & y0 | x0 & !y0 | ... | x19 & !y19
+        let terms = (0..20).map(|i| format!("x{} y{}& x{} y{}!&|", i, i, i, i));
+        let wide = terms.reduce(|acc, term| format!("{} {}|", acc, term)).unwrap();
+        let result = minimize(&crate::parse(&wide).unwrap());
+        assert_eq!(result.vars().len(), 20);
+        assert!(!result.to_string().contains('&'), "Not minimal: {}", result);
+    }
+}
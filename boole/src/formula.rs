@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Not;
+
+use crate::var::Var;
+
+/// Truth values for the variables of a formula.
+/// Variables missing from the map read as `false`.
+pub type Assignment = BTreeMap<Var, bool>;
+
+/// Row `index` of a truth table over `vars`, counting from the all-false
+/// row with the first variable as the most significant bit.
+pub fn row(vars: &[Var], index: u64) -> Assignment {
+    let n = vars.len();
+    vars.iter().enumerate().map(|(i, &var)| (var, (index >> (n - 1 - i)) & 1 == 1)).collect()
+}
+
+/// A Boolean formula as a tree.
+///
+/// Binary variants keep their operands in RPN order: `AB>` is
+/// `Imply(A, B)`, i.e. `A > B`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Formula {
+    Const(bool),
+    Var(Var),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Xor(Box<Formula>, Box<Formula>),
+    Imply(Box<Formula>, Box<Formula>),
+    Equiv(Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    pub fn var(name: impl Into<Var>) -> Formula {
+        Formula::Var(name.into())
+    }
+
+    pub fn and(a: Formula, b: Formula) -> Formula {
+        Formula::And(Box::new(a), Box::new(b))
+    }
+
+    pub fn or(a: Formula, b: Formula) -> Formula {
+        Formula::Or(Box::new(a), Box::new(b))
+    }
+
+    pub fn xor(a: Formula, b: Formula) -> Formula {
+        Formula::Xor(Box::new(a), Box::new(b))
+    }
+
+    pub fn imply(a: Formula, b: Formula) -> Formula {
+        Formula::Imply(Box::new(a), Box::new(b))
+    }
+
+    pub fn equiv(a: Formula, b: Formula) -> Formula {
+        Formula::Equiv(Box::new(a), Box::new(b))
+    }
+
+    /// Builds the binary node for an RPN operator symbol (`&`, `|`, `^`, `>`, `=`).
+    pub fn binary(op: char, a: Formula, b: Formula) -> Option<Formula> {
+        match op {
+            '&' => Some(Formula::and(a, b)),
+            '|' => Some(Formula::or(a, b)),
+            '^' => Some(Formula::xor(a, b)),
+            '>' => Some(Formula::imply(a, b)),
+            '=' => Some(Formula::equiv(a, b)),
+            _ => None,
+        }
+    }
+
+    /// The RPN symbol of the root node, `None` for a variable.
+    pub fn symbol(&self) -> Option<char> {
+        match self {
+            Formula::Const(false) => Some('0'),
+            Formula::Const(true) => Some('1'),
+            Formula::Var(_) => None,
+            Formula::Not(_) => Some('!'),
+            Formula::And(..) => Some('&'),
+            Formula::Or(..) => Some('|'),
+            Formula::Xor(..) => Some('^'),
+            Formula::Imply(..) => Some('>'),
+            Formula::Equiv(..) => Some('='),
+        }
+    }
+
+    /// Both operands of a binary node, `None` for constants, variables and `!`.
+    pub fn operands(&self) -> Option<(&Formula, &Formula)> {
+        match self {
+            Formula::And(a, b)
+            | Formula::Or(a, b)
+            | Formula::Xor(a, b)
+            | Formula::Imply(a, b)
+            | Formula::Equiv(a, b) => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    pub fn eval(&self, assignment: &Assignment) -> bool {
+        match self {
+            Formula::Const(value) => *value,
+            Formula::Var(name) => assignment.get(name).copied().unwrap_or(false),
+            Formula::Not(a) => !a.eval(assignment),
+            Formula::And(a, b) => a.eval(assignment) && b.eval(assignment),
+            Formula::Or(a, b) => a.eval(assignment) || b.eval(assignment),
+            Formula::Xor(a, b) => a.eval(assignment) != b.eval(assignment),
+            Formula::Imply(a, b) => !a.eval(assignment) || b.eval(assignment),
+            Formula::Equiv(a, b) => a.eval(assignment) == b.eval(assignment),
+        }
+    }
+
+    /// Evaluates 64 assignments at once: bit `k` of a variable's word is its
+    /// value in assignment `k`, and bit `k` of the result is the formula's.
+    /// Variables missing from the map are `false` in every assignment.
+    pub fn eval_lanes(&self, lanes: &BTreeMap<Var, u64>) -> u64 {
+        match self {
+            Formula::Const(value) => if *value { !0 } else { 0 },
+            Formula::Var(name) => lanes.get(name).copied().unwrap_or(0),
+            Formula::Not(a) => !a.eval_lanes(lanes),
+            Formula::And(a, b) => a.eval_lanes(lanes) & b.eval_lanes(lanes),
+            Formula::Or(a, b) => a.eval_lanes(lanes) | b.eval_lanes(lanes),
+            Formula::Xor(a, b) => a.eval_lanes(lanes) ^ b.eval_lanes(lanes),
+            Formula::Imply(a, b) => !a.eval_lanes(lanes) | b.eval_lanes(lanes),
+            Formula::Equiv(a, b) => !(a.eval_lanes(lanes) ^ b.eval_lanes(lanes)),
+        }
+    }
+
+    /// The variables used by the formula, sorted by name.
+    pub fn vars(&self) -> BTreeSet<Var> {
+        let mut vars = BTreeSet::new();
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    /// The formula with each variable for which `replace` returns a formula
+    /// replaced by it, such as a constant to fix its value.
+    pub fn substitute(&self, replace: &impl Fn(Var) -> Option<Formula>) -> Formula {
+        match self {
+            Formula::Const(_) => self.clone(),
+            Formula::Var(name) => replace(*name).unwrap_or_else(|| self.clone()),
+            Formula::Not(a) => !a.substitute(replace),
+            _ => {
+                let (a, b) = self.operands().unwrap();
+                Formula::binary(self.symbol().unwrap(), a.substitute(replace), b.substitute(replace)).unwrap()
+            }
+        }
+    }
+
+    fn collect_vars(&self, vars: &mut BTreeSet<Var>) {
+        match self {
+            Formula::Const(_) => {}
+            Formula::Var(name) => {
+                vars.insert(*name);
+            }
+            Formula::Not(a) => a.collect_vars(vars),
+            _ => {
+                let (a, b) = self.operands().unwrap();
+                a.collect_vars(vars);
+                b.collect_vars(vars);
+            }
+        }
+    }
+}
+
+impl Not for Formula {
+    type Output = Formula;
+
+    fn not(self) -> Formula {
+        Formula::Not(Box::new(self))
+    }
+}
@@ -0,0 +1,274 @@
+use crate::error::ParseError;
+use crate::formula::Formula;
+use crate::rpn;
+use crate::var::{self, Var};
+
+/// Parses a formula written in infix notation, such as `(A & B) | !C`.
+///
+/// Operators from loosest to tightest binding:
+///
+/// | Operator       | Meaning     | Associativity |
+/// |----------------|-------------|---------------|
+/// | `<->` or `=`   | equivalence | left          |
+/// | `->` or `>`    | implication | right         |
+/// | `\|`           | or          | left          |
+/// | `^`            | xor         | left          |
+/// | `&`            | and         | left          |
+/// | `!`            | not         | prefix        |
+///
+/// Operands are `0`, `1`, variables (see [`Var`]) and parenthesized formulas.
+///
+/// ```
+/// let f = boole::infix::parse("A -> B -> C").unwrap();
+/// assert_eq!(f.to_string(), "ABC>>");
+/// ```
+pub fn parse(formula: &str) -> Result<Formula, ParseError> {
+    let tokens = tokenize(formula)?;
+    if tokens.is_empty() {
+        return Err(ParseError::EmptyInput);
+    }
+
+    let mut parser = Parser { tokens: &tokens, pos: 0, end: formula.len() };
+    let f = parser.expr(0)?;
+    match parser.peek() {
+        None => Ok(f),
+        Some(_) => Err(ParseError::ExpectedOperator { offset: parser.offset() }),
+    }
+}
+
+/// Prints a formula in infix notation with only the parentheses needed to
+/// parse it back into the same tree.
+///
+/// ```
+/// let f = boole::rpn::parse("AB&C!|").unwrap();
+/// assert_eq!(boole::infix::to_infix(&f), "A & B | !C");
+/// ```
+pub fn to_infix(f: &Formula) -> String {
+    let mut out = String::new();
+    write(f, &PLAIN, &mut out);
+    out
+}
+
+/// The formula in infix for LaTeX math mode, parenthesized as by
+/// [`to_infix`].
+///
+/// ```
+/// let f = boole::parse("AB&C!|D>").unwrap();
+/// assert_eq!(boole::infix::to_latex(&f), "A \\land B \\lor \\lnot C \\to D");
+/// ```
+pub fn to_latex(f: &Formula) -> String {
+    let mut out = String::new();
+    write(f, &LATEX, &mut out);
+    out
+}
+
+/// Converts an infix formula to RPN.
+pub fn to_rpn(formula: &str) -> Result<String, ParseError> {
+    parse(formula).map(|f| f.to_string())
+}
+
+/// Converts an RPN formula to infix.
+pub fn from_rpn(formula: &str) -> Result<String, ParseError> {
+    rpn::parse(formula).map(|f| to_infix(&f))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Const(bool),
+    Var(Var),
+    Not,
+    Binary(char),
+    Open,
+    Close,
+}
+
+// Binding power of each binary operator, loosest first, and whether it groups to the right.
+fn precedence(op: char) -> (u8, bool) {
+    match op {
+        '=' => (1, false),
+        '>' => (2, true),
+        '|' => (3, false),
+        '^' => (4, false),
+        '&' => (5, false),
+        _ => unreachable!(),
+    }
+}
+
+fn tokenize(formula: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while let Some(ch) = formula[offset..].chars().next() {
+        if let Some(scanned) = var::scan(formula, offset) {
+            let (name, len) = scanned?;
+            tokens.push((offset, Token::Var(name)));
+            offset += len;
+            continue;
+        }
+
+        let rest = &formula[offset..];
+        let (token, len) = match ch {
+            '0' => (Token::Const(false), 1),
+            '1' => (Token::Const(true), 1),
+            '!' => (Token::Not, 1),
+            '&' | '|' | '^' | '>' | '=' => (Token::Binary(ch), 1),
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '-' if rest.starts_with("->") => (Token::Binary('>'), 2),
+            '<' if rest.starts_with("<->") => (Token::Binary('='), 3),
+            _ if ch.is_whitespace() => {
+                offset += ch.len_utf8();
+                continue;
+            }
+            _ => return Err(ParseError::UnknownSymbol { symbol: ch, offset }),
+        };
+        tokens.push((offset, token));
+        offset += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+    // Offset reported for errors found after the last token
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|&(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |&(offset, _)| offset)
+    }
+
+    // Precedence climbing: parses operators binding at least as tight as `min`.
+    fn expr(&mut self, min: u8) -> Result<Formula, ParseError> {
+        let mut lhs = self.operand()?;
+
+        while let Some(Token::Binary(op)) = self.peek() {
+            let (prec, right) = precedence(op);
+            if prec < min {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expr(if right { prec } else { prec + 1 })?;
+            lhs = Formula::binary(op, lhs, rhs).unwrap();
+        }
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<Formula, ParseError> {
+        let offset = self.offset();
+        let token = self.peek().ok_or(ParseError::ExpectedOperand { offset })?;
+        self.pos += 1;
+
+        match token {
+            Token::Const(value) => Ok(Formula::Const(value)),
+            Token::Var(name) => Ok(Formula::Var(name)),
+            Token::Not => Ok(!self.operand()?),
+            Token::Open => {
+                let f = self.expr(0)?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(f)
+                    }
+                    None => Err(ParseError::UnclosedParen { offset }),
+                    Some(_) => Err(ParseError::ExpectedOperator { offset: self.offset() }),
+                }
+            }
+            Token::Binary(_) | Token::Close => Err(ParseError::ExpectedOperand { offset }),
+        }
+    }
+}
+
+// How operators and names are written.
+struct Notation {
+    not: &'static str,
+    // The binary operator with its surrounding spaces
+    binary: fn(char) -> &'static str,
+    var: fn(Var) -> String,
+}
+
+const PLAIN: Notation = Notation {
+    not: "!",
+    binary: |op| match op {
+        '>' => " -> ",
+        '=' => " <-> ",
+        '&' => " & ",
+        '|' => " | ",
+        _ => " ^ ",
+    },
+    var: |var| var.token().into_owned(),
+};
+
+const LATEX: Notation = Notation {
+    not: "\\lnot ",
+    binary: |op| match op {
+        '>' => " \\to ",
+        '=' => " \\leftrightarrow ",
+        '&' => " \\land ",
+        '|' => " \\lor ",
+        _ => " \\oplus ",
+    },
+    var: |var| {
+        let name = var.name();
+        if name.chars().count() == 1 && name.chars().all(|c| c.is_ascii_alphabetic()) {
+            return name.to_string();
+        }
+        let mut escaped = String::new();
+        for c in name.chars() {
+            match c {
+                '_' | '&' | '%' | '$' | '#' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\\' => escaped += "\\backslash ",
+                _ => escaped.push(c),
+            }
+        }
+        format!("\\mathit{{{}}}", escaped)
+    },
+};
+
+fn write(f: &Formula, notation: &Notation, out: &mut String) {
+    match f {
+        Formula::Var(name) => out.push_str(&(notation.var)(*name)),
+        Formula::Const(_) => out.push(f.symbol().unwrap()),
+        Formula::Not(a) => {
+            out.push_str(notation.not);
+            write_operand(a, a.operands().is_some(), notation, out);
+        }
+        _ => {
+            let (a, b) = f.operands().unwrap();
+            let op = f.symbol().unwrap();
+            let (prec, right) = precedence(op);
+            // An operand needs parentheses if it binds looser than `f`, or
+            // equally tight on the side the operator does not group towards.
+            let needs_parens = |child: &Formula, is_right: bool| match child.operands() {
+                Some(_) => {
+                    let child_prec = precedence(child.symbol().unwrap()).0;
+                    child_prec < prec || (child_prec == prec && is_right != right)
+                }
+                None => false,
+            };
+
+            write_operand(a, needs_parens(a, false), notation, out);
+            out.push_str((notation.binary)(op));
+            write_operand(b, needs_parens(b, true), notation, out);
+        }
+    }
+}
+
+fn write_operand(f: &Formula, parens: bool, notation: &Notation, out: &mut String) {
+    if parens {
+        out.push('(');
+        write(f, notation, out);
+        out.push(')');
+    } else {
+        write(f, notation, out);
+    }
+}
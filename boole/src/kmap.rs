@@ -0,0 +1,274 @@
+//! Karnaugh maps of formulas of up to 6 variables.
+//!
+//! The first half of the variables (in name order) label the rows and the
+//! rest the columns, both counted in Gray code so neighbouring cells differ
+//! in one variable, and so do the first and last row or column. Past four
+//! variables the map is the reflected kind: cells mirrored across the middle
+//! line of a half are neighbours too.
+
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use crate::arith::gray_code;
+use crate::canonical;
+use crate::formula::Formula;
+use crate::minimize::{self, Implicant};
+use crate::var::Var;
+
+const MAX_VARS: usize = 6;
+
+/// Why a formula has no Karnaugh map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KmapError {
+    /// More than 6 variables.
+    TooManyVars { vars: usize },
+}
+
+impl fmt::Display for KmapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KmapError::TooManyVars { vars } => write!(f, "a Karnaugh map holds at most {} variables, not {}", MAX_VARS, vars),
+        }
+    }
+}
+
+impl Error for KmapError {}
+
+/// The truth table of a formula laid out as a Karnaugh map.
+pub struct KarnaughMap {
+    vars: Vec<Var>,
+    row_vars: usize,
+    // Indexed by truth table row, first variable as the most significant bit
+    values: Vec<bool>,
+    groups: Vec<Implicant>,
+}
+
+impl KarnaughMap {
+    /// The map of a formula, without groups. Fails past 6 variables.
+    pub fn new(f: &Formula) -> Result<KarnaughMap, KmapError> {
+        let vars: Vec<Var> = f.vars().into_iter().collect();
+        if vars.len() > MAX_VARS {
+            return Err(KmapError::TooManyVars { vars: vars.len() });
+        }
+        let mut values = vec![false; 1 << vars.len()];
+        for i in canonical::minterms(f, &vars) {
+            values[i as usize] = true;
+        }
+        Ok(KarnaughMap { row_vars: vars.len() / 2, vars, values, groups: Vec::new() })
+    }
+
+    /// Marks the prime implicants of a minimal sum of products, see
+    /// [`minimize::minimal_cover`].
+    pub fn with_groups(mut self) -> KarnaughMap {
+        let minterms: Vec<u64> = (0..self.values.len() as u64).filter(|&i| self.values[i as usize]).collect();
+        self.groups = minimize::minimal_cover(self.vars.len(), &minterms, &[]);
+        self
+    }
+
+    pub fn groups(&self) -> &[Implicant] {
+        &self.groups
+    }
+
+    fn col_vars(&self) -> usize {
+        self.vars.len() - self.row_vars
+    }
+
+    // The Gray code of each row or column, in display order.
+    fn codes(bits: usize) -> Vec<u64> {
+        (0..1u32 << bits).map(|i| gray_code(i) as u64).collect()
+    }
+
+    fn label(&self, vars: &[Var]) -> String {
+        let separator = if vars.iter().all(|var| var.name().len() == 1) { "" } else { "," };
+        vars.iter().map(|var| var.name()).collect::<Vec<_>>().join(separator)
+    }
+
+    fn bits(code: u64, bits: usize) -> String {
+        (0..bits).rev().map(|bit| if code >> bit & 1 == 1 { '1' } else { '0' }).collect()
+    }
+
+    fn cell(&self, row: u64, col: u64) -> u64 {
+        row << self.col_vars() | col
+    }
+
+    // Letters of the groups covering a cell, `a` for the first group.
+    fn group_letters(&self, cell: u64) -> String {
+        (b'a'..).zip(&self.groups).filter(|(_, group)| group.covers(cell)).map(|(letter, _)| letter as char).collect()
+    }
+
+    /// The map as a Markdown table, like ex04's truth table. Each cell holds
+    /// the value, then the letters of the groups covering it, listed below
+    /// the table with their terms.
+    ///
+    /// ```text
+    /// | AB\CD | 00  | 01  | 11  | 10  |
+    /// |-------|-----|-----|-----|-----|
+    /// | 00    | 0   | 0   | 0   | 0   |
+    /// | 01    | 0   | 1a  | 1a  | 0   |
+    /// | 11    | 1b  | 1ab | 1a  | 0   |
+    /// | 10    | 1b  | 1b  | 0   | 0   |
+    /// a: BD& (-1-1)
+    /// b: AC!& (1-0-)
+    /// ```
+    pub fn to_ascii(&self) -> String {
+        let (row_codes, col_codes) = (Self::codes(self.row_vars), Self::codes(self.col_vars()));
+        let corner = format!("{}\\{}", self.label(&self.vars[..self.row_vars]), self.label(&self.vars[self.row_vars..]));
+        let text = |row: u64, col: u64| {
+            let cell = self.cell(row, col);
+            format!("{}{}", self.values[cell as usize] as u8, self.group_letters(cell))
+        };
+        let width = col_codes
+            .iter()
+            .flat_map(|&col| row_codes.iter().map(move |&row| (row, col)))
+            .map(|(row, col)| text(row, col).len())
+            .max()
+            .unwrap()
+            .max(self.col_vars());
+        let first = corner.len().max(self.row_vars);
+
+        let mut out = format!("| {:first$} |", corner);
+        for &col in &col_codes {
+            write!(out, " {:width$} |", Self::bits(col, self.col_vars())).unwrap();
+        }
+        write!(out, "\n|{}|", "-".repeat(first + 2)).unwrap();
+        for _ in &col_codes {
+            write!(out, "{}|", "-".repeat(width + 2)).unwrap();
+        }
+        out.push('\n');
+        for &row in &row_codes {
+            write!(out, "| {:first$} |", Self::bits(row, self.row_vars)).unwrap();
+            for &col in &col_codes {
+                write!(out, " {:width$} |", text(row, col)).unwrap();
+            }
+            out.push('\n');
+        }
+
+        for (letter, group) in (b'a'..).zip(&self.groups) {
+            writeln!(out, "{}: {} ({})", letter as char, group.to_formula(&self.vars), group.pattern(self.vars.len())).unwrap();
+        }
+        out
+    }
+
+    /// The map as an SVG image, with each group drawn as a rounded
+    /// rectangle, split in pieces where it wraps around an edge.
+    pub fn to_svg(&self) -> String {
+        const CELL: usize = 48;
+        const MARGIN: usize = 72;
+        const COLORS: [&str; 8] = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324"];
+
+        let (row_codes, col_codes) = (Self::codes(self.row_vars), Self::codes(self.col_vars()));
+        let (width, height) = (MARGIN + CELL * col_codes.len() + 8, MARGIN + CELL * row_codes.len() + 8);
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"14\" text-anchor=\"middle\">\n",
+            width, height
+        );
+        let corner = format!("{}\\{}", self.label(&self.vars[..self.row_vars]), self.label(&self.vars[self.row_vars..]));
+        writeln!(out, "  <text x=\"{}\" y=\"{}\">{}</text>", MARGIN / 2, MARGIN / 2, escape(&corner)).unwrap();
+
+        for (i, &col) in col_codes.iter().enumerate() {
+            let x = MARGIN + i * CELL + CELL / 2;
+            writeln!(out, "  <text x=\"{}\" y=\"{}\">{}</text>", x, MARGIN - 12, Self::bits(col, self.col_vars())).unwrap();
+        }
+        for (i, &row) in row_codes.iter().enumerate() {
+            let y = MARGIN + i * CELL + CELL / 2 + 5;
+            writeln!(out, "  <text x=\"{}\" y=\"{}\">{}</text>", MARGIN - 24, y, Self::bits(row, self.row_vars)).unwrap();
+            for (j, &col) in col_codes.iter().enumerate() {
+                let (x, top) = (MARGIN + j * CELL, MARGIN + i * CELL);
+                let value = self.values[self.cell(row, col) as usize] as u8;
+                writeln!(out, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>", x, top, CELL, CELL).unwrap();
+                writeln!(out, "  <text x=\"{}\" y=\"{}\">{}</text>", x + CELL / 2, y, value).unwrap();
+            }
+        }
+
+        for (g, group) in self.groups.iter().enumerate() {
+            let color = COLORS[g % COLORS.len()];
+            let inset = 3 + 3 * (g % 4);
+            let rows = runs(&row_codes, |row| self.group_spans(group, row, true));
+            let cols = runs(&col_codes, |col| self.group_spans(group, col, false));
+            for &(top, bottom) in &rows {
+                for &(left, right) in &cols {
+                    writeln!(
+                        out,
+                        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"12\" fill=\"{}\" fill-opacity=\"0.2\" stroke=\"{}\" stroke-width=\"2\"/>",
+                        MARGIN + left * CELL + inset,
+                        MARGIN + top * CELL + inset,
+                        (right - left + 1) * CELL - 2 * inset,
+                        (bottom - top + 1) * CELL - 2 * inset,
+                        color,
+                        color
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        out += "</svg>\n";
+        out
+    }
+
+    // Whether the group covers some cell of a row (or of a column).
+    fn group_spans(&self, group: &Implicant, code: u64, row: bool) -> bool {
+        let shift = if row { self.col_vars() } else { 0 };
+        let bits = if row { self.row_vars } else { self.col_vars() };
+        let part = |x: u64| x >> shift & ((1 << bits) - 1);
+        code & !part(group.mask) == part(group.value)
+    }
+}
+
+// The maximal runs of consecutive positions, as first and last index, whose
+// code satisfies `inside`.
+fn runs(codes: &[u64], inside: impl Fn(u64) -> bool) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (i, &code) in codes.iter().enumerate() {
+        if !inside(code) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == i => *last = i,
+            _ => runs.push((i, i)),
+        }
+    }
+    runs
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_of_a_minimal_cover() {
+        // Rows and columns in Gray code
+        let map = KarnaughMap::new(&crate::parse("AC!&BD&|").unwrap()).unwrap().with_groups();
+        let expected = "\
+| AB\\CD | 00  | 01  | 11  | 10  |
+|-------|-----|-----|-----|-----|
+| 00    | 0   | 0   | 0   | 0   |
+| 01    | 0   | 1a  | 1a  | 0   |
+| 11    | 1b  | 1ab | 1a  | 0   |
+| 10    | 1b  | 1b  | 0   | 0   |
+a: BD& (-1-1)
+b: AC!& (1-0-)
+";
+        assert_eq!(map.to_ascii(), expected);
+    }
+
+    #[test]
+    fn corners_wrap_around() {
+        // B!D! is one group, drawn as four pieces
+        let map = KarnaughMap::new(&crate::parse("B!D!&AA!|&CC!|&").unwrap()).unwrap().with_groups();
+        assert_eq!(map.groups().len(), 1);
+        assert_eq!(map.to_svg().matches("<rect").count(), 16 + 4);
+    }
+
+    #[test]
+    fn at_most_six_variables() {
+        let map = KarnaughMap::new(&crate::parse("ABCDEF^^^^^").unwrap()).unwrap();
+        assert_eq!(map.to_ascii().lines().count(), 2 + 8);
+        let result = KarnaughMap::new(&crate::parse("ABCDEFG||||||").unwrap()).err();
+        assert_eq!(result, Some(KmapError::TooManyVars { vars: 7 }));
+        assert_eq!(result.unwrap().to_string(), "a Karnaugh map holds at most 6 variables, not 7");
+    }
+}
@@ -0,0 +1,52 @@
+//! Shared core of the "Ready, Set, Boole!" exercises.
+//!
+//! Formulas are parsed once into a [`Formula`] tree and every exercise
+//! (evaluation, truth tables, normal forms, satisfiability, set evaluation)
+//! works on that tree instead of re-scanning the RPN string with its own stack.
+//! Formulas can be written in RPN ([`rpn`]) or infix ([`infix`]) notation.
+
+pub mod arith;
+pub mod bdd;
+pub mod canonical;
+pub mod cnf;
+pub mod curve;
+pub mod dimacs;
+pub mod dnf;
+pub mod espresso;
+pub mod error;
+pub mod formula;
+pub mod infix;
+pub mod kmap;
+pub mod minimize;
+pub mod models;
+pub mod nnf;
+pub mod pla;
+pub mod program;
+pub mod rpn;
+pub mod sat;
+pub mod sets;
+pub mod solver;
+pub mod synth;
+pub mod truth;
+pub mod tseitin;
+pub mod var;
+
+pub use error::ParseError;
+pub use formula::{Assignment, Formula};
+pub use var::Var;
+
+/// Parses a formula written either in RPN (`AB&C!|`) or in infix (`A & B | !C`).
+///
+/// No string is valid in both notations with different meanings, so RPN is
+/// tried first. When both fail, the infix error is reported if RPN stumbled
+/// on a symbol it does not know (such as `(` or `->`) or if infix got further
+/// into the input.
+pub fn parse(formula: &str) -> Result<Formula, ParseError> {
+    rpn::parse(formula).or_else(|rpn_error| {
+        infix::parse(formula).map_err(|infix_error| match rpn_error {
+            ParseError::UnknownSymbol { .. } => infix_error,
+            _ if infix_error.offset() > rpn_error.offset() => infix_error,
+            _ => rpn_error,
+        })
+    })
+}
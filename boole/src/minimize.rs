@@ -0,0 +1,221 @@
+//! Two-level minimization with Quine–McCluskey and Petrick's method.
+//!
+//! Minterms are truth table rows numbered as in [`canonical`](crate::canonical),
+//! first variable as the most significant bit. Don't-care rows may be covered
+//! or not, whichever gives the smaller formula.
+
+use std::collections::BTreeSet;
+
+use crate::canonical;
+use crate::cnf::{chain, Lit};
+use crate::formula::Formula;
+use crate::var::Var;
+
+/// A product term as a cube: the rows agreeing with `value` on every bit
+/// outside `mask`. With 3 variables, `value: 0b100, mask: 0b001` is `10-`,
+/// i.e. `A & !B`, covering rows 4 and 5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Implicant {
+    pub value: u64,
+    pub mask: u64,
+}
+
+impl Implicant {
+    pub fn minterm(row: u64) -> Implicant {
+        Implicant { value: row, mask: 0 }
+    }
+
+    pub fn covers(self, row: u64) -> bool {
+        row & !self.mask == self.value
+    }
+
+    /// The number of literals of the term.
+    pub fn literals(self, num_vars: usize) -> usize {
+        num_vars - self.mask.count_ones() as usize
+    }
+
+    /// The cube written one character per variable: `1`, `0`, or `-` when
+    /// the variable does not appear.
+    pub fn pattern(self, num_vars: usize) -> String {
+        (0..num_vars)
+            .rev()
+            .map(|bit| match (self.mask >> bit & 1, self.value >> bit & 1) {
+                (1, _) => '-',
+                (_, 1) => '1',
+                _ => '0',
+            })
+            .collect()
+    }
+
+    /// The term as a conjunction of literals, `1` if it has none.
+    pub fn to_formula(self, vars: &[Var]) -> Formula {
+        let n = vars.len();
+        let lits = vars.iter().enumerate().filter_map(|(i, &var)| {
+            let bit = n - 1 - i;
+            (self.mask >> bit & 1 == 0).then(|| Lit { var, positive: self.value >> bit & 1 == 1 }.to_formula())
+        });
+        chain(lits.collect(), Formula::and, true)
+    }
+}
+
+/// Every prime implicant of the function true on `minterms`, where rows in
+/// `dont_cares` may be taken either way.
+///
+/// Cubes differing in a single variable are merged, `0110` and `0111`
+/// into `011-`, until nothing merges; cubes that never merged are prime.
+pub fn prime_implicants(num_vars: usize, minterms: &[u64], dont_cares: &[u64]) -> Vec<Implicant> {
+    let mut cubes: BTreeSet<Implicant> = minterms.iter().chain(dont_cares).map(|&row| Implicant::minterm(row)).collect();
+    let mut primes = Vec::new();
+
+    while !cubes.is_empty() {
+        let mut merged = BTreeSet::new();
+        let mut used = BTreeSet::new();
+        for &cube in &cubes {
+            for bit in (0..num_vars).map(|bit| 1u64 << bit) {
+                if cube.mask & bit != 0 || cube.value & bit != 0 {
+                    continue;
+                }
+                let other = Implicant { value: cube.value | bit, mask: cube.mask };
+                if cubes.contains(&other) {
+                    merged.insert(Implicant { value: cube.value, mask: cube.mask | bit });
+                    used.insert(cube);
+                    used.insert(other);
+                }
+            }
+        }
+        primes.extend(cubes.difference(&used));
+        cubes = merged;
+    }
+
+    primes.sort();
+    primes
+}
+
+/// A smallest set of prime implicants covering `minterms`: fewest terms,
+/// then fewest literals.
+///
+/// Essential primes, the only ones covering some minterm, are taken first.
+/// Petrick's method then finds the exact cover of what is left by expanding
+/// the product, over each uncovered minterm, of the sum of primes covering
+/// it.
+pub fn minimal_cover(num_vars: usize, minterms: &[u64], dont_cares: &[u64]) -> Vec<Implicant> {
+    let primes = prime_implicants(num_vars, minterms, dont_cares);
+    let covering = |row: u64| -> Vec<usize> { (0..primes.len()).filter(|&p| primes[p].covers(row)).collect() };
+
+    let mut chosen: BTreeSet<usize> = BTreeSet::new();
+    for &row in minterms {
+        if let [only] = covering(row)[..] {
+            chosen.insert(only);
+        }
+    }
+
+    let uncovered = minterms.iter().filter(|&&row| !chosen.iter().any(|&p| primes[p].covers(row)));
+    let mut products: Vec<BTreeSet<usize>> = vec![BTreeSet::new()];
+    for &row in uncovered {
+        let sum = covering(row);
+        let mut expanded: Vec<BTreeSet<usize>> = Vec::new();
+        for product in &products {
+            if product.iter().any(|p| sum.contains(p)) {
+                expanded.push(product.clone());
+                continue;
+            }
+            for &p in &sum {
+                let mut product = product.clone();
+                product.insert(p);
+                expanded.push(product);
+            }
+        }
+        products = absorb(expanded);
+    }
+
+    let cost = |product: &BTreeSet<usize>| {
+        let literals: usize = product.iter().map(|&p| primes[p].literals(num_vars)).sum();
+        (product.len(), literals)
+    };
+    let best = products.into_iter().min_by_key(cost).unwrap_or_default();
+    chosen.extend(best);
+    chosen.into_iter().map(|p| primes[p]).collect()
+}
+
+// Removes products containing another product: X + XY = X.
+fn absorb(mut products: Vec<BTreeSet<usize>>) -> Vec<BTreeSet<usize>> {
+    products.sort_by_key(|product| product.len());
+    products.dedup();
+    let mut kept: Vec<BTreeSet<usize>> = Vec::new();
+    for product in products {
+        if !kept.iter().any(|smaller| smaller.is_subset(&product)) {
+            kept.push(product);
+        }
+    }
+    kept
+}
+
+/// The sum of the terms of `cover` over `vars`, `0` if it is empty.
+pub fn to_formula(vars: &[Var], cover: &[Implicant]) -> Formula {
+    let terms = cover.iter().map(|implicant| implicant.to_formula(vars));
+    chain(terms.collect(), Formula::or, false)
+}
+
+/// A minimal sum of products equivalent to the formula.
+///
+/// ```
+/// let f = boole::parse("AB&AB!&|C&").unwrap();
+/// assert_eq!(boole::minimize::minimize(&f).to_string(), "AC&");
+/// ```
+pub fn minimize(f: &Formula) -> Formula {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let minterms = canonical::minterms(f, &vars);
+    to_formula(&vars, &minimal_cover(vars.len(), &minterms, &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_sums_of_products() {
+        // The formula, then the prime implicants chosen
+        let tests = [
+            ("AB&AB!&|C&", "AC&", vec!["1-1"]),
+            ("AB^AB&|", "BA|", vec!["-1", "1-"]),
+            ("ABC&&A!BC&&|AB!C&&|", "BC&AC&|", vec!["-11", "1-1"]),
+            ("AB=", "A!B!&AB&|", vec!["00", "11"]),
+            ("AA!|", "1", vec!["-"]),
+            ("AA!&", "0", vec![]),
+        ];
+        for (input, expected, cubes) in tests {
+            let f = crate::parse(input).unwrap();
+            let vars: Vec<Var> = f.vars().into_iter().collect();
+            let cover = minimal_cover(vars.len(), &canonical::minterms(&f, &vars), &[]);
+            assert_eq!(minimize(&f).to_string(), expected, "Test failed for {}", input);
+            let patterns: Vec<String> = cover.iter().map(|implicant| implicant.pattern(vars.len())).collect();
+            assert_eq!(patterns, cubes, "Test failed for {}", input);
+        }
+    }
+
+    #[test]
+    fn dont_cares() {
+        // f(A, B, C, D) = Σm(4, 8, 10, 11, 12, 15) + d(9, 14): three terms of
+        // two or three literals, the classic textbook example
+        let cover = minimal_cover(4, &[4, 8, 10, 11, 12, 15], &[9, 14]);
+        let patterns: Vec<String> = cover.iter().map(|implicant| implicant.pattern(4)).collect();
+        assert_eq!(patterns, ["-100", "10--", "1-1-"]);
+    }
+
+    #[test]
+    fn cyclic_cover() {
+        // No essential prime, resolved by Petrick's method: six primes, two
+        // covers of three
+        let minterms = [0, 1, 2, 5, 6, 7];
+        assert_eq!(prime_implicants(3, &minterms, &[]).len(), 6);
+        let cover = minimal_cover(3, &minterms, &[]);
+        assert_eq!(cover.len(), 3);
+        assert!(minterms.iter().all(|&row| cover.iter().any(|implicant| implicant.covers(row))));
+        assert!(!cover.iter().any(|implicant| implicant.covers(3) || implicant.covers(4)));
+    }
+
+    #[test]
+    fn pattern() {
+        assert_eq!(Implicant { value: 0b100, mask: 0b001 }.pattern(3), "10-");
+    }
+}
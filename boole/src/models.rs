@@ -0,0 +1,356 @@
+//! Enumerating and counting the satisfying assignments of a formula.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::cnf::{Cnf, Encoding};
+use crate::formula::{Assignment, Formula};
+use crate::program::Program;
+use crate::solver::Solver;
+use crate::truth::Words;
+use crate::var::Var;
+
+/// Iterator over every satisfying assignment of a formula, see [`models`].
+pub struct Models {
+    solver: Solver,
+    vars: Vec<Var>,
+}
+
+/// Every satisfying assignment of the formula's variables, each exactly once.
+///
+/// After each model the solver is given a blocking clause ruling it out, so
+/// models come out one SAT call at a time without enumerating the 2^n
+/// assignments that fail.
+pub fn models(f: &Formula) -> Models {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let (clauses, num_vars) = dense_clauses(f, &vars);
+    let mut solver = Solver::new();
+    // Variables the CNF does not mention still get both values
+    solver.reserve_vars(num_vars);
+    for clause in &clauses {
+        solver.add_clause(clause);
+    }
+    Models { solver, vars }
+}
+
+impl Iterator for Models {
+    type Item = Assignment;
+
+    fn next(&mut self) -> Option<Assignment> {
+        if !self.solver.solve() {
+            return None;
+        }
+        let model = self.solver.model();
+        let blocking: Vec<i32> = (1..=self.vars.len() as i32)
+            .map(|v| if model[v as usize - 1] { -v } else { v })
+            .collect();
+        // Auxiliary variables follow the formula's and are left out
+        let assignment = self.vars.iter().copied().zip(model.iter().copied()).collect();
+        self.solver.add_clause(&blocking);
+        Some(assignment)
+    }
+}
+
+/// The number of satisfying assignments of the formula's variables.
+///
+/// Exhaustive DPLL search with the tricks of dedicated model counters:
+/// a variable no remaining clause mentions is counted as a factor of 2
+/// instead of being branched on, clauses sharing no variable are counted
+/// separately and multiplied, and the counts of sub-problems are cached.
+///
+/// ```
+/// let f = boole::parse("A | B").unwrap();
+/// assert_eq!(boole::models::count_models(&f), 3u64.into());
+/// ```
+pub fn count_models(f: &Formula) -> Count {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let (clauses, num_vars) = dense_clauses(f, &vars);
+    let mut counter = Counter { cache: HashMap::new() };
+    counter.count(clauses, num_vars)
+}
+
+/// The number of models read off the truth table, 64 rows at a time with
+/// the formula compiled once. Exponential: for formulas of a few dozen
+/// variables at most, and to cross-check [`count_models`].
+pub fn count_brute_force(f: &Formula) -> Count {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let program = Program::compile(f, &vars);
+    Words::new(&program).map(|word| word.count_ones() as u64).sum::<u64>().into()
+}
+
+// The Tseitin CNF of `f` with variables numbered from 1 in the order of
+// `vars`, then the auxiliary variables, and the number of variables. Each
+// auxiliary variable is determined by the others, so models correspond one
+// to one with the formula's.
+fn dense_clauses(f: &Formula, vars: &[Var]) -> (Vec<Vec<i32>>, usize) {
+    let cnf = Cnf::encode(f, Encoding::Tseitin);
+    let mut index: HashMap<Var, i32> = vars.iter().zip(1..).map(|(&var, i)| (var, i)).collect();
+    for var in cnf.vars() {
+        let next = index.len() as i32 + 1;
+        index.entry(var).or_insert(next);
+    }
+    let clauses = cnf
+        .clauses
+        .iter()
+        .map(|clause| {
+            clause
+                .iter()
+                .map(|lit| if lit.positive { index[&lit.var] } else { -index[&lit.var] })
+                .collect()
+        })
+        .collect();
+    (clauses, index.len())
+}
+
+struct Counter {
+    // Counts of components already solved, keyed by their sorted clauses
+    cache: HashMap<Vec<Vec<i32>>, Count>,
+}
+
+impl Counter {
+    // Counts the assignments of `num_vars` variables, including every
+    // variable of `clauses`, that satisfy `clauses`.
+    fn count(&mut self, clauses: Vec<Vec<i32>>, num_vars: usize) -> Count {
+        let Some((clauses, assigned)) = propagate(clauses) else {
+            return Count::from(0);
+        };
+
+        let used: BTreeSet<u32> = clauses.iter().flatten().map(|lit| lit.unsigned_abs()).collect();
+        let mut total = Count::power_of_two(num_vars - assigned - used.len());
+        for component in components(clauses) {
+            total = total.mul(&self.count_component(component));
+            if total.is_zero() {
+                break;
+            }
+        }
+        total
+    }
+
+    fn count_component(&mut self, mut clauses: Vec<Vec<i32>>) -> Count {
+        for clause in &mut clauses {
+            clause.sort_unstable();
+        }
+        clauses.sort_unstable();
+        if let Some(count) = self.cache.get(&clauses) {
+            return count.clone();
+        }
+
+        let num_vars = clauses.iter().flatten().map(|lit| lit.unsigned_abs()).collect::<BTreeSet<_>>().len();
+        let branch = most_frequent_var(&clauses);
+        let mut total = Count::from(0);
+        for lit in [branch, -branch] {
+            let mut assigned = clauses.clone();
+            assigned.push(vec![lit]);
+            total = total.add(&self.count(assigned, num_vars));
+        }
+
+        self.cache.insert(clauses, total.clone());
+        total
+    }
+}
+
+// Applies unit clauses until none is left. Returns the remaining clauses and
+// how many variables were assigned, or `None` on a conflict.
+fn propagate(mut clauses: Vec<Vec<i32>>) -> Option<(Vec<Vec<i32>>, usize)> {
+    let mut assigned = 0;
+    while let Some(unit) = clauses.iter().find(|clause| clause.len() <= 1) {
+        let lit = *unit.first()?;
+        assigned += 1;
+        clauses.retain(|clause| !clause.contains(&lit));
+        for clause in &mut clauses {
+            clause.retain(|&other| other != -lit);
+            if clause.is_empty() {
+                return None;
+            }
+        }
+    }
+    Some((clauses, assigned))
+}
+
+// Splits clauses into groups sharing no variable.
+fn components(clauses: Vec<Vec<i32>>) -> Vec<Vec<Vec<i32>>> {
+    let mut parent: HashMap<u32, u32> = HashMap::new();
+    fn find(parent: &mut HashMap<u32, u32>, v: u32) -> u32 {
+        let p = *parent.entry(v).or_insert(v);
+        if p == v {
+            return v;
+        }
+        let root = find(parent, p);
+        parent.insert(v, root);
+        root
+    }
+
+    for clause in &clauses {
+        let first = find(&mut parent, clause[0].unsigned_abs());
+        for lit in &clause[1..] {
+            let root = find(&mut parent, lit.unsigned_abs());
+            parent.insert(root, first);
+        }
+    }
+
+    let mut groups: HashMap<u32, Vec<Vec<i32>>> = HashMap::new();
+    for clause in clauses {
+        let root = find(&mut parent, clause[0].unsigned_abs());
+        groups.entry(root).or_default().push(clause);
+    }
+    groups.into_values().collect()
+}
+
+fn most_frequent_var(clauses: &[Vec<i32>]) -> i32 {
+    let mut occurrences: HashMap<i32, usize> = HashMap::new();
+    for lit in clauses.iter().flatten() {
+        *occurrences.entry(lit.abs()).or_default() += 1;
+    }
+    // Ties go to the lowest variable so counting is deterministic
+    occurrences.into_iter().max_by_key(|&(v, n)| (n, -v)).unwrap().0
+}
+
+/// An exact model count, which for hundreds of variables exceeds any
+/// machine integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Count {
+    // Base 2^64 digits, least significant first, without trailing zeros
+    digits: Vec<u64>,
+}
+
+impl Count {
+    pub fn power_of_two(exponent: usize) -> Count {
+        let mut digits = vec![0; exponent / 64 + 1];
+        digits[exponent / 64] = 1 << (exponent % 64);
+        Count { digits }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The count as a machine integer, if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.digits[..] {
+            [] => Some(0),
+            [low] => Some(low as u128),
+            [low, high] => Some((high as u128) << 64 | low as u128),
+            _ => None,
+        }
+    }
+
+    pub fn add(&self, other: &Count) -> Count {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0u128;
+        for i in 0..len {
+            let sum = carry + *self.digits.get(i).unwrap_or(&0) as u128 + *other.digits.get(i).unwrap_or(&0) as u128;
+            digits.push(sum as u64);
+            carry = sum >> 64;
+        }
+        digits.push(carry as u64);
+        Count::trimmed(digits)
+    }
+
+    pub fn mul(&self, other: &Count) -> Count {
+        let mut digits = vec![0u64; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let product = digits[i + j] as u128 + a as u128 * b as u128 + carry;
+                digits[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            digits[i + other.digits.len()] = carry as u64;
+        }
+        Count::trimmed(digits)
+    }
+
+    fn trimmed(mut digits: Vec<u64>) -> Count {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Count { digits }
+    }
+}
+
+impl From<u64> for Count {
+    fn from(n: u64) -> Count {
+        Count::trimmed(vec![n])
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Peel off 19 decimal digits at a time, the most a u64 holds
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut digits = self.digits.clone();
+        let mut chunks = Vec::new();
+        while !digits.is_empty() {
+            let mut remainder = 0u128;
+            for digit in digits.iter_mut().rev() {
+                let value = remainder << 64 | *digit as u128;
+                *digit = (value / CHUNK) as u64;
+                remainder = value % CHUNK;
+            }
+            chunks.push(remainder as u64);
+            digits = Count::trimmed(digits).digits;
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{:019}", chunk)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    // Formulas and their number of models
+    const COUNTS: [(&str, u64); 6] = [
+        ("AB|", 3),
+        ("AB&", 1),
+        ("AA!&", 0),
+        ("AB^C|", 6),
+        ("AB>BC>&AC>|", 6),
+        ("x1 x2 x3 x4 ||| x1! x2! x3! x4! |||&", 14),
+    ];
+
+    #[test]
+    fn models_are_distinct_and_satisfying() {
+        for (formula, expected) in COUNTS {
+            let f = crate::parse(formula).unwrap();
+            let found: Vec<Assignment> = models(&f).collect();
+            assert!(found.iter().all(|model| f.eval(model)), "Non-model found for {}", formula);
+            let distinct: BTreeSet<&Assignment> = found.iter().collect();
+            assert_eq!(found.len() as u64, expected, "Test failed for {}", formula);
+            assert_eq!(distinct.len(), found.len(), "Duplicate model for {}", formula);
+        }
+    }
+
+    #[test]
+    fn count_models_agrees_with_the_truth_table() {
+        for (formula, expected) in COUNTS {
+            let f = crate::parse(formula).unwrap();
+            let vars: Vec<Var> = f.vars().into_iter().collect();
+            let rows = (0..1u64 << vars.len()).filter(|&row| f.eval(&crate::formula::row(&vars, row))).count();
+            assert_eq!(rows as u64, expected, "Test failed for {}", formula);
+            assert_eq!(count_models(&f), expected.into(), "Test failed for {}", formula);
+            assert_eq!(count_brute_force(&f), expected.into(), "Test failed for {}", formula);
+        }
+    }
+
+    #[test]
+    fn free_variables_are_counted_without_enumeration() {
+        // 200 variables of which one is constrained: 2^199
+        let wide = (1..200).fold(String::from("x0"), |acc, i| format!("{} x{} x{}!|&", acc, i, i));
+        let count = count_models(&crate::parse(&format!("{} x0&", wide)).unwrap());
+        assert_eq!(count, Count::power_of_two(199));
+        assert_eq!(count.to_string(), "803469022129495137770981046170581301261101496891396417650688");
+    }
+}
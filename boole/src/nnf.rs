@@ -0,0 +1,62 @@
+use crate::formula::Formula;
+
+/// Rewrites a formula into negation normal form: only `&`, `|` and `!`
+/// remain, and every `!` applies directly to a variable.
+pub fn to_nnf(f: &Formula) -> Formula {
+    nnf(f, false)
+}
+
+/// Tells whether a formula is in negation normal form.
+///
+/// ```
+/// use boole::nnf::{is_nnf, to_nnf};
+///
+/// let f = boole::parse("AB&C|!").unwrap();
+/// assert!(!is_nnf(&f));
+/// assert!(is_nnf(&to_nnf(&f)));
+/// ```
+pub fn is_nnf(f: &Formula) -> bool {
+    match f {
+        Formula::Const(_) | Formula::Var(_) => true,
+        Formula::Not(a) => matches!(**a, Formula::Var(_)),
+        Formula::And(a, b) | Formula::Or(a, b) => is_nnf(a) && is_nnf(b),
+        Formula::Xor(..) | Formula::Imply(..) | Formula::Equiv(..) => false,
+    }
+}
+
+// `negated` tracks whether an odd number of `!` sits above `f`.
+fn nnf(f: &Formula, negated: bool) -> Formula {
+    match f {
+        Formula::Const(value) => Formula::Const(*value != negated),
+        Formula::Var(_) if negated => !f.clone(),
+        Formula::Var(_) => f.clone(),
+        Formula::Not(a) => nnf(a, !negated),
+        // De Morgan: !(A & B) = !A | !B
+        Formula::And(a, b) if negated => Formula::or(nnf(a, true), nnf(b, true)),
+        Formula::And(a, b) => Formula::and(nnf(a, false), nnf(b, false)),
+        // De Morgan: !(A | B) = !A & !B
+        Formula::Or(a, b) if negated => Formula::and(nnf(a, true), nnf(b, true)),
+        Formula::Or(a, b) => Formula::or(nnf(a, false), nnf(b, false)),
+        // A > B = !A | B, so !(A > B) = A & !B
+        Formula::Imply(a, b) if negated => Formula::and(nnf(a, false), nnf(b, true)),
+        Formula::Imply(a, b) => Formula::or(nnf(a, true), nnf(b, false)),
+        // A = B = (A & B) | (!A & !B), and !(A = B) is A ^ B
+        Formula::Equiv(a, b) => equal(a, b, negated),
+        // A ^ B = (A & !B) | (!A & B), and !(A ^ B) is A = B
+        Formula::Xor(a, b) => equal(a, b, !negated),
+    }
+}
+
+fn equal(a: &Formula, b: &Formula, negated: bool) -> Formula {
+    if negated {
+        Formula::or(
+            Formula::and(nnf(a, false), nnf(b, true)),
+            Formula::and(nnf(a, true), nnf(b, false)),
+        )
+    } else {
+        Formula::or(
+            Formula::and(nnf(a, false), nnf(b, false)),
+            Formula::and(nnf(a, true), nnf(b, true)),
+        )
+    }
+}
@@ -0,0 +1,215 @@
+//! The Berkeley PLA format read and written by Espresso:
+//!
+//! ```text
+//! .i 3
+//! .o 2
+//! .ilb a b c
+//! .ob f g
+//! .p 2
+//! 1-0 10
+//! -11 11
+//! .e
+//! ```
+//!
+//! Each product line gives the input part, one character per input (`1`,
+//! `0`, or `-` for absent), then the output part, one character per output:
+//! `1` puts the term in that output's on-set, `-` in its don't-care set, and
+//! `0` or `~` leaves it out. Only this `fd` type is supported.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::espresso::{espresso, Cube};
+
+/// A multi-output function as a list of cubes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pla {
+    pub inputs: usize,
+    pub outputs: usize,
+    /// Names from `.ilb`, empty if the file has none.
+    pub input_names: Vec<String>,
+    /// Names from `.ob`, empty if the file has none.
+    pub output_names: Vec<String>,
+    pub on: Vec<Cube>,
+    pub dc: Vec<Cube>,
+}
+
+/// Why a PLA file could not be read. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaError {
+    /// A product line before both `.i` and `.o`.
+    MissingDimensions { line: usize },
+    /// More than 64 inputs or outputs.
+    TooLarge { line: usize },
+    /// A directive that is unknown, unsupported or malformed.
+    BadDirective { line: usize, directive: String },
+    /// A product line whose parts have the wrong length or characters.
+    BadProduct { line: usize },
+    /// The number of product lines differs from `.p`.
+    ProductCountMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for PlaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlaError::MissingDimensions { line } => write!(f, "line {}: expected '.i' and '.o' before products", line),
+            PlaError::TooLarge { line } => write!(f, "line {}: at most 64 inputs and 64 outputs are supported", line),
+            PlaError::BadDirective { line, directive } => write!(f, "line {}: unsupported directive '{}'", line, directive),
+            PlaError::BadProduct { line } => write!(f, "line {}: malformed product term", line),
+            PlaError::ProductCountMismatch { expected, found } => {
+                write!(f, "'.p' declares {} products but {} were found", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for PlaError {}
+
+impl Pla {
+    /// Reads a PLA file. Text after `#` is a comment, and `.e` or `.end`
+    /// ends the file.
+    pub fn parse(input: &str) -> Result<Pla, PlaError> {
+        let mut pla = Pla::default();
+        let (mut inputs, mut outputs, mut products) = (None, None, None);
+        let mut found = 0;
+
+        for (n, text) in input.lines().enumerate() {
+            let line = n + 1;
+            let text = text.split('#').next().unwrap().trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            if text.starts_with('.') {
+                let mut fields = text.split_whitespace();
+                let directive = fields.next().unwrap();
+                let bad = || PlaError::BadDirective { line, directive: directive.to_string() };
+                let number = |fields: std::str::SplitWhitespace| -> Result<usize, PlaError> {
+                    let fields: Vec<&str> = fields.collect();
+                    match fields[..] {
+                        [value] => value.parse().map_err(|_| bad()),
+                        _ => Err(bad()),
+                    }
+                };
+                match directive {
+                    ".i" => inputs = Some(number(fields)?),
+                    ".o" => outputs = Some(number(fields)?),
+                    ".p" => products = Some(number(fields)?),
+                    ".ilb" => pla.input_names = fields.map(String::from).collect(),
+                    ".ob" => pla.output_names = fields.map(String::from).collect(),
+                    ".type" if fields.next() == Some("fd") => {}
+                    ".e" | ".end" => break,
+                    _ => return Err(bad()),
+                }
+                if inputs.is_some_and(|n| n > 64) || outputs.is_some_and(|n| n > 64) {
+                    return Err(PlaError::TooLarge { line });
+                }
+                continue;
+            }
+
+            let (Some(num_inputs), Some(num_outputs)) = (inputs, outputs) else {
+                return Err(PlaError::MissingDimensions { line });
+            };
+            // The two parts are usually separated by a space, but need not be
+            let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+            if chars.len() != num_inputs + num_outputs {
+                return Err(PlaError::BadProduct { line });
+            }
+            let (mut on, mut dc) = (Cube::universe(num_inputs, 0), Cube::universe(num_inputs, 0));
+            for (i, &c) in chars[..num_inputs].iter().enumerate() {
+                match c {
+                    '1' => on.zeros &= !(1 << i),
+                    '0' => on.ones &= !(1 << i),
+                    '-' => {}
+                    _ => return Err(PlaError::BadProduct { line }),
+                }
+            }
+            for (j, &c) in chars[num_inputs..].iter().enumerate() {
+                match c {
+                    '1' => on.outputs |= 1 << j,
+                    '-' => dc.outputs |= 1 << j,
+                    '0' | '~' => {}
+                    _ => return Err(PlaError::BadProduct { line }),
+                }
+            }
+            found += 1;
+            if on.outputs != 0 {
+                pla.on.push(on);
+            }
+            if dc.outputs != 0 {
+                pla.dc.push(Cube { outputs: dc.outputs, ..on });
+            }
+        }
+
+        let (Some(num_inputs), Some(num_outputs)) = (inputs, outputs) else {
+            return Err(PlaError::MissingDimensions { line: input.lines().count() + 1 });
+        };
+        if let Some(expected) = products.filter(|&expected| expected != found) {
+            return Err(PlaError::ProductCountMismatch { expected, found });
+        }
+        pla.inputs = num_inputs;
+        pla.outputs = num_outputs;
+        Ok(pla)
+    }
+
+    /// Writes the on-set then the don't-care set as a PLA file.
+    pub fn write(&self) -> String {
+        let mut out = format!(".i {}\n.o {}\n", self.inputs, self.outputs);
+        if !self.input_names.is_empty() {
+            out += &format!(".ilb {}\n", self.input_names.join(" "));
+        }
+        if !self.output_names.is_empty() {
+            out += &format!(".ob {}\n", self.output_names.join(" "));
+        }
+        out += &format!(".p {}\n", self.on.len() + self.dc.len());
+        for (cubes, mark) in [(&self.on, '1'), (&self.dc, '-')] {
+            for cube in cubes {
+                let outputs: String = (0..self.outputs).map(|j| if cube.outputs >> j & 1 == 1 { mark } else { '0' }).collect();
+                out += &format!("{} {}\n", cube.pattern(self.inputs), outputs);
+            }
+        }
+        out += ".e\n";
+        out
+    }
+
+    /// The same function with its on-set minimized by Espresso and no
+    /// don't-cares left.
+    pub fn minimize(&self) -> Pla {
+        Pla {
+            on: espresso(&self.on, &self.dc, self.inputs, self.outputs),
+            dc: Vec::new(),
+            input_names: self.input_names.clone(),
+            output_names: self.output_names.clone(),
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimize_two_outputs() {
+        // f = a & !c | b & c, g = b & c | a & b with a don't-care, minimized
+        // to f = a | b & c, g = a & b | b & c
+        let input = ".i 3\n.o 2\n.ilb a b c\n.ob f g\n.p 5\n1-0 10\n-11 11\n110 01\n111 01\n101 -0\n.e\n";
+        let result = Pla::parse(input).unwrap().minimize().write();
+        assert_eq!(result, ".i 3\n.o 2\n.ilb a b c\n.ob f g\n.p 3\n11- 01\n1-- 10\n-11 11\n.e\n");
+        assert_eq!(Pla::parse(&result).unwrap().write(), result, "Round trip failed");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let errors = [
+            ("10 1\n", PlaError::MissingDimensions { line: 1 }),
+            (".i 2\n.o 1\n1 1\n", PlaError::BadProduct { line: 3 }),
+            (".i 2\n.o 1\n.p 2\n10 1\n", PlaError::ProductCountMismatch { expected: 2, found: 1 }),
+            (".i 2\n.o 1\n.type fr\n", PlaError::BadDirective { line: 3, directive: ".type".to_string() }),
+            (".i 65\n", PlaError::TooLarge { line: 1 }),
+        ];
+        for (input, expected) in errors {
+            assert_eq!(Pla::parse(input), Err(expected), "Test failed for {:?}", input);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Formulas compiled to a flat program for a stack machine.
+//!
+//! The instructions are the formula's RPN, with each variable resolved to a
+//! slot number, so running the program walks a vector instead of a tree and
+//! never looks a name up. Compiling also computes the deepest the stack gets,
+//! so a stack of that size is allocated once and every run after is
+//! allocation-free.
+//!
+//! Values are `u64` words of 64 independent assignments, as in
+//! [`truth`](crate::truth); a single assignment is one bit of them.
+
+use std::collections::HashMap;
+
+use crate::formula::Formula;
+use crate::var::Var;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Const(bool),
+    /// Pushes the word of a slot.
+    Load(u32),
+    Not,
+    And,
+    Or,
+    Xor,
+    Imply,
+    Equiv,
+}
+
+impl Op {
+    // Values popped and pushed.
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Op::Const(_) | Op::Load(_) => (0, 1),
+            Op::Not => (1, 1),
+            _ => (2, 1),
+        }
+    }
+}
+
+/// A compiled formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    code: Vec<Op>,
+    vars: Vec<Var>,
+    depth: usize,
+}
+
+impl Program {
+    /// Compiles the formula with slot `i` for `vars[i]`. Variables of the
+    /// formula missing from `vars` are `false`, as in [`Formula::eval`].
+    ///
+    /// ```
+    /// use boole::program::{Op, Program};
+    ///
+    /// let f = boole::parse("AB&C|").unwrap();
+    /// let program = Program::compile(&f, &['C'.into(), 'B'.into(), 'A'.into()]);
+    /// assert_eq!(program.code(), [Op::Load(2), Op::Load(1), Op::And, Op::Load(0), Op::Or]);
+    /// assert_eq!(program.depth(), 2);
+    /// ```
+    pub fn compile(f: &Formula, vars: &[Var]) -> Program {
+        let slots: HashMap<Var, u32> = vars.iter().zip(0..).map(|(&var, slot)| (var, slot)).collect();
+        let mut code = Vec::new();
+        emit(f, &slots, &mut code);
+        let depth = verify(&code).expect("a formula compiles to a well-formed program");
+        Program { code, vars: vars.to_vec(), depth }
+    }
+
+    pub fn code(&self) -> &[Op] {
+        &self.code
+    }
+
+    /// The variable of each slot.
+    pub fn vars(&self) -> &[Var] {
+        &self.vars
+    }
+
+    /// The most values on the stack at once while running.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// A stack to run the program with, to reuse across runs.
+    pub fn stack(&self) -> Vec<u64> {
+        vec![0; self.depth]
+    }
+
+    /// Runs the program on 64 assignments at once: bit `k` of `slots[i]` is
+    /// the value of variable `i` in assignment `k`, and bit `k` of the result
+    /// is the formula's. `stack` must hold [`depth`](Program::depth) values.
+    pub fn eval_lanes(&self, slots: &[u64], stack: &mut [u64]) -> u64 {
+        assert!(stack.len() >= self.depth, "stack of {} for a program of depth {}", stack.len(), self.depth);
+        let mut top = 0;
+        for &op in &self.code {
+            match op {
+                Op::Const(value) => {
+                    stack[top] = if value { !0 } else { 0 };
+                    top += 1;
+                }
+                Op::Load(slot) => {
+                    stack[top] = slots[slot as usize];
+                    top += 1;
+                }
+                Op::Not => stack[top - 1] = !stack[top - 1],
+                _ => {
+                    top -= 1;
+                    let (a, b) = (stack[top - 1], stack[top]);
+                    stack[top - 1] = match op {
+                        Op::And => a & b,
+                        Op::Or => a | b,
+                        Op::Xor => a ^ b,
+                        Op::Imply => !a | b,
+                        _ => !(a ^ b),
+                    };
+                }
+            }
+        }
+        stack[0]
+    }
+
+    /// Runs the program on one assignment, `values[i]` for slot `i`.
+    pub fn eval(&self, values: &[bool], stack: &mut [u64]) -> bool {
+        assert!(values.len() <= 64, "at most 64 slots can be given as bits");
+        let slots: [u64; 64] = std::array::from_fn(|i| if values.get(i) == Some(&true) { 1 } else { 0 });
+        self.eval_lanes(&slots[..values.len()], stack) & 1 == 1
+    }
+}
+
+// Appends the formula in RPN, operands first.
+fn emit(f: &Formula, slots: &HashMap<Var, u32>, code: &mut Vec<Op>) {
+    match f {
+        Formula::Const(value) => code.push(Op::Const(*value)),
+        Formula::Var(var) => code.push(slots.get(var).map_or(Op::Const(false), |&slot| Op::Load(slot))),
+        Formula::Not(a) => {
+            emit(a, slots, code);
+            code.push(Op::Not);
+        }
+        _ => {
+            let (a, b) = f.operands().unwrap();
+            emit(a, slots, code);
+            emit(b, slots, code);
+            code.push(match f {
+                Formula::And(..) => Op::And,
+                Formula::Or(..) => Op::Or,
+                Formula::Xor(..) => Op::Xor,
+                Formula::Imply(..) => Op::Imply,
+                _ => Op::Equiv,
+            });
+        }
+    }
+}
+
+// The maximum stack depth, or `None` if an instruction would pop an empty
+// stack or the program does not leave exactly one value.
+fn verify(code: &[Op]) -> Option<usize> {
+    let (mut depth, mut max) = (0usize, 0);
+    for op in code {
+        let (pops, pushes) = op.arity();
+        depth = depth.checked_sub(pops)? + pushes;
+        max = max.max(depth);
+    }
+    (depth == 1).then_some(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiled_once() {
+        // Slots in the order given, and the stack as deep as the
+        // right-nested RPN needs
+        let f = crate::parse("x0 x1 x2 x3 x4 &&&&!").unwrap();
+        let vars: Vec<Var> = f.vars().into_iter().collect();
+        let program = Program::compile(&f, &vars);
+        let mut stack = program.stack();
+        assert_eq!(program.code().len(), 10);
+        assert_eq!(program.depth(), 5);
+        for i in 0..32u64 {
+            let values: Vec<bool> = (0..5).map(|bit| i >> (4 - bit) & 1 == 1).collect();
+            assert_eq!(program.eval(&values, &mut stack), i != 31, "Row {} differs", i);
+        }
+        assert!(Program::compile(&f, &vars[..4]).eval(&[true; 4], &mut stack), "x4 missing is false");
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use crate::error::ParseError;
+use crate::formula::Formula;
+use crate::var::{self, Var};
+
+/// Parses a formula in reverse Polish notation.
+///
+/// Accepts the constants `0` and `1`, variables (see [`Var`] for how names
+/// are written) and the operators `!`, `&`, `|`, `^`, `>` and `=`.
+/// Whitespace is ignored except to separate two names.
+///
+/// ```
+/// let f = boole::rpn::parse("AB&C|").unwrap();
+/// assert_eq!(f.to_string(), "AB&C|");
+///
+/// let f = boole::rpn::parse("req_valid grant[3]&").unwrap();
+/// assert_eq!(f.vars().len(), 2);
+/// ```
+pub fn parse(formula: &str) -> Result<Formula, ParseError> {
+    // Each operand remembers where it starts, to point at leftovers.
+    let mut stack: Vec<(usize, Formula)> = Vec::new();
+    let mut offset = 0;
+
+    while let Some(ch) = formula[offset..].chars().next() {
+        if let Some(scanned) = var::scan(formula, offset) {
+            let (name, len) = scanned?;
+            stack.push((offset, Formula::Var(name)));
+            offset += len;
+            continue;
+        }
+
+        let underflow = |depth| ParseError::OperandUnderflow { op: ch, offset, depth };
+        match ch {
+            '0' => stack.push((offset, Formula::Const(false))),
+            '1' => stack.push((offset, Formula::Const(true))),
+            '!' => {
+                let (start, a) = stack.pop().ok_or(underflow(0))?;
+                stack.push((start, !a));
+            }
+            '&' | '|' | '^' | '>' | '=' => {
+                if stack.len() < 2 {
+                    return Err(underflow(stack.len()));
+                }
+                let (_, b) = stack.pop().unwrap();
+                let (start, a) = stack.pop().unwrap();
+                stack.push((start, Formula::binary(ch, a, b).unwrap()));
+            }
+            _ if ch.is_whitespace() => {}
+            _ => return Err(ParseError::UnknownSymbol { symbol: ch, offset }),
+        }
+        offset += ch.len_utf8();
+    }
+
+    match stack.len() {
+        0 => Err(ParseError::EmptyInput),
+        1 => Ok(stack.pop().unwrap().1),
+        depth => Err(ParseError::LeftoverOperands { offset: stack[1].0, depth }),
+    }
+}
+
+/// Prints the formula back in reverse Polish notation.
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write(self, &mut None, f)
+    }
+}
+
+// `open` holds the last variable written if its name could run into the next token.
+fn write(formula: &Formula, open: &mut Option<Var>, f: &mut fmt::Formatter) -> fmt::Result {
+    match formula {
+        Formula::Var(name) => {
+            if open.is_some() {
+                f.write_str(" ")?;
+            }
+            *open = Some(*name).filter(|name| name.is_open_ended());
+            return f.write_str(&name.token());
+        }
+        Formula::Const(_) => {
+            if open.is_some() {
+                f.write_str(" ")?;
+            }
+        }
+        Formula::Not(a) => write(a, open, f)?,
+        _ => {
+            let (a, b) = formula.operands().unwrap();
+            write(a, open, f)?;
+            write(b, open, f)?;
+        }
+    }
+    *open = None;
+    write!(f, "{}", formula.symbol().unwrap())
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::cnf::{Cnf, Encoding};
+use crate::formula::{self, Assignment, Formula};
+use crate::program::Program;
+use crate::solver::Solver;
+use crate::truth::Words;
+use crate::var::Var;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SatResult {
+    /// A satisfying assignment of every variable of the clauses.
+    Sat(Assignment),
+    Unsat,
+}
+
+/// Tells whether some assignment of the formula's variables makes it true.
+pub fn sat(f: &Formula) -> bool {
+    matches!(solve(f), SatResult::Sat(_))
+}
+
+/// Finds an assignment making the formula true. The model assigns every
+/// variable of the formula, including ones the CNF conversion dropped.
+///
+/// The formula is encoded with Plaisted–Greenbaum, so its size stays linear;
+/// the auxiliary variables are left out of the model.
+///
+/// ```
+/// use boole::sat::{solve, SatResult};
+///
+/// let f = boole::parse("A & !B").unwrap();
+/// let SatResult::Sat(model) = solve(&f) else { panic!() };
+/// assert!(f.eval(&model));
+/// ```
+pub fn solve(f: &Formula) -> SatResult {
+    match solve_cnf(&Cnf::encode(f, Encoding::PlaistedGreenbaum)) {
+        SatResult::Sat(full) => {
+            let model: Assignment = f
+                .vars()
+                .into_iter()
+                .map(|var| (var, full.get(&var).copied().unwrap_or(false)))
+                .collect();
+            debug_assert!(f.eval(&model));
+            SatResult::Sat(model)
+        }
+        SatResult::Unsat => SatResult::Unsat,
+    }
+}
+
+/// Finds the first model in truth table order by trying every assignment,
+/// 64 at a time with the formula compiled once. Exponential: for small
+/// formulas, and to cross-check [`solve`].
+pub fn brute_force(f: &Formula) -> SatResult {
+    let vars: Vec<Var> = f.vars().into_iter().collect();
+    let program = Program::compile(f, &vars);
+    match Words::new(&program).enumerate().find(|&(_, word)| word != 0) {
+        Some((w, word)) => SatResult::Sat(formula::row(&vars, 64 * w as u64 + word.trailing_zeros() as u64)),
+        None => SatResult::Unsat,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Equivalence {
+    Equivalent,
+    /// An assignment of the variables of both formulas where they differ.
+    Counterexample(Assignment),
+}
+
+/// Tells whether two formulas agree on every assignment, by asking the
+/// solver for a model of `f ^ g`. Unlike comparing truth tables this stays
+/// practical with many variables.
+///
+/// ```
+/// use boole::sat::{equivalent, Equivalence};
+///
+/// let f = boole::parse("AB&!").unwrap();
+/// assert_eq!(equivalent(&f, &boole::parse("A!B!|").unwrap()), Equivalence::Equivalent);
+/// let Equivalence::Counterexample(row) = equivalent(&f, &boole::parse("A!B!&").unwrap()) else { panic!() };
+/// assert_eq!(row[&'A'.into()], !row[&'B'.into()]);
+/// ```
+pub fn equivalent(f: &Formula, g: &Formula) -> Equivalence {
+    match solve(&Formula::xor(f.clone(), g.clone())) {
+        SatResult::Sat(model) => Equivalence::Counterexample(model),
+        SatResult::Unsat => Equivalence::Equivalent,
+    }
+}
+
+/// Whether a formula is always true, never true, or depends on its
+/// variables, with assignments proving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Classification {
+    /// True under every assignment: the negation is unsatisfiable.
+    Tautology,
+    /// False under every assignment, such as `falsifying`.
+    Contradiction { falsifying: Assignment },
+    Contingent { satisfying: Assignment, falsifying: Assignment },
+}
+
+impl fmt::Display for Classification {
+    /// `tautology`, `contradiction (false for A=0, B=1)` or
+    /// `contingent (true for A=1; false for A=0)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Classification::Tautology => write!(f, "tautology"),
+            Classification::Contradiction { falsifying } => {
+                write!(f, "contradiction (false for {})", show(falsifying))
+            }
+            Classification::Contingent { satisfying, falsifying } => {
+                write!(f, "contingent (true for {}; false for {})", show(satisfying), show(falsifying))
+            }
+        }
+    }
+}
+
+fn show(assignment: &Assignment) -> String {
+    if assignment.is_empty() {
+        return "no variables".to_string();
+    }
+    let values: Vec<String> = assignment.iter().map(|(var, &value)| format!("{}={}", var, value as u8)).collect();
+    values.join(", ")
+}
+
+/// Classifies a formula with two solver calls, on its negation then on the
+/// formula itself.
+///
+/// ```
+/// use boole::sat::{classify, Classification};
+///
+/// assert_eq!(classify(&boole::parse("AA!|").unwrap()), Classification::Tautology);
+/// assert_eq!(classify(&boole::parse("AB>").unwrap()).to_string(), "contingent (true for A=0, B=0; false for A=1, B=0)");
+/// ```
+pub fn classify(f: &Formula) -> Classification {
+    let SatResult::Sat(falsifying) = solve(&!f.clone()) else {
+        return Classification::Tautology;
+    };
+    match solve(f) {
+        SatResult::Sat(satisfying) => Classification::Contingent { satisfying, falsifying },
+        SatResult::Unsat => Classification::Contradiction { falsifying },
+    }
+}
+
+/// Runs the CDCL solver on clauses.
+pub fn solve_cnf(cnf: &Cnf) -> SatResult {
+    let vars: Vec<Var> = cnf.vars().into_iter().collect();
+    let index: HashMap<Var, i32> = vars.iter().zip(1..).map(|(&var, i)| (var, i)).collect();
+
+    let mut solver = Solver::new();
+    solver.reserve_vars(vars.len());
+    for clause in &cnf.clauses {
+        let lits: Vec<i32> = clause
+            .iter()
+            .map(|lit| if lit.positive { index[&lit.var] } else { -index[&lit.var] })
+            .collect();
+        solver.add_clause(&lits);
+    }
+
+    if solver.solve() {
+        SatResult::Sat(vars.into_iter().zip(solver.model().iter().copied()).collect())
+    } else {
+        SatResult::Unsat
+    }
+}
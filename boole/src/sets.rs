@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+use crate::error::ParseError;
+use crate::formula::Formula;
+use crate::var::Var;
+
+/// Why a formula could not be evaluated over sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetError {
+    /// The formula did not parse; `From` lets a front-end parse and
+    /// evaluate with `?`.
+    Parse(ParseError),
+    /// A variable past the last set given, or not a letter from `A` to `Z`.
+    MissingSet { var: Var, sets: usize },
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SetError::Parse(e) => write!(f, "{}", e),
+            SetError::MissingSet { var, sets } => write!(f, "no set for variable '{}' among the {} given", var, sets),
+        }
+    }
+}
+
+impl Error for SetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SetError::Parse(e) => Some(e),
+            SetError::MissingSet { .. } => None,
+        }
+    }
+}
+
+impl From<ParseError> for SetError {
+    fn from(e: ParseError) -> SetError {
+        SetError::Parse(e)
+    }
+}
+
+/// All subsets of `set`, in the order of the binary counter selecting them.
+pub fn powerset(set: &[i32]) -> Vec<Vec<i32>> {
+    (0..1u64 << set.len())
+        .map(|i| {
+            set.iter()
+                .enumerate()
+                .filter(|(j, _)| (i >> j) & 1 == 1)
+                .map(|(_, &x)| x)
+                .collect()
+        })
+        .collect()
+}
+
+/// Evaluates a formula over sets: variable `A` is `sets[0]`, `B` is `sets[1]`
+/// and so on up to `Z`, and negation is the complement relative to the union
+/// of all sets.
+///
+/// The result is sorted.
+pub fn eval_set(f: &Formula, sets: &[Vec<i32>]) -> Result<Vec<i32>, SetError> {
+    let universe: BTreeSet<i32> = sets.iter().flatten().copied().collect();
+    let result = eval(f, sets, &universe)?;
+    Ok(result.into_iter().collect())
+}
+
+fn eval(f: &Formula, sets: &[Vec<i32>], universe: &BTreeSet<i32>) -> Result<BTreeSet<i32>, SetError> {
+    let complement = |s: &BTreeSet<i32>| universe.difference(s).copied().collect::<BTreeSet<_>>();
+
+    Ok(match f {
+        Formula::Const(false) => BTreeSet::new(),
+        Formula::Const(true) => universe.clone(),
+        Formula::Var(name) => {
+            let set = match name.name().as_bytes() {
+                [letter @ b'A'..=b'Z'] => sets.get((letter - b'A') as usize),
+                _ => None,
+            };
+            match set {
+                Some(set) => set.iter().copied().collect(),
+                None => return Err(SetError::MissingSet { var: *name, sets: sets.len() }),
+            }
+        }
+        Formula::Not(a) => complement(&eval(a, sets, universe)?),
+        _ => {
+            let (a, b) = f.operands().unwrap();
+            let a = eval(a, sets, universe)?;
+            let b = eval(b, sets, universe)?;
+            match f {
+                Formula::And(..) => &a & &b,
+                Formula::Or(..) => &a | &b,
+                Formula::Xor(..) => &a ^ &b,
+                Formula::Imply(..) => &complement(&a) | &b,
+                Formula::Equiv(..) => &complement(&a) ^ &b,
+                _ => unreachable!(),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_set_reports_missing_sets() {
+        let f = crate::parse("AB&C|").unwrap();
+        let result = eval_set(&f, &[vec![1, 2], vec![2, 3]]);
+        assert_eq!(result, Err(SetError::MissingSet { var: 'C'.into(), sets: 2 }));
+        assert_eq!(result.unwrap_err().to_string(), "no set for variable 'C' among the 2 given");
+        let f = crate::parse("x A|").unwrap();
+        assert_eq!(eval_set(&f, &[vec![1]]), Err(SetError::MissingSet { var: "x".into(), sets: 1 }));
+    }
+
+    #[test]
+    fn parse_errors_convert() {
+        let eval = |formula: &str| -> Result<Vec<i32>, SetError> { eval_set(&crate::parse(formula)?, &[vec![1], vec![2]]) };
+        assert_eq!(eval("AB|"), Ok(vec![1, 2]));
+        assert!(matches!(eval("AB|&"), Err(SetError::Parse(_))));
+    }
+}
@@ -0,0 +1,557 @@
+//! Conflict-driven clause learning (CDCL) SAT solver.
+//!
+//! Works on dense variable indices with DIMACS-style literals: variable `v`
+//! (numbered from 1) is `v` when positive and `-v` when negated.
+//!
+//! - Unit propagation with two watched literals per clause.
+//! - First-UIP conflict analysis with learnt clause minimization.
+//! - VSIDS branching with phase saving.
+//! - Restarts following the Luby sequence.
+//! - Deletion of the least active half of the learnt clauses.
+
+// Internally literal `2 * v + 1` is the negation of `2 * v`, for 0-based `v`.
+type Lit = u32;
+
+fn var(lit: Lit) -> usize {
+    (lit >> 1) as usize
+}
+
+fn make_lit(var: usize, value: bool) -> Lit {
+    ((var as Lit) << 1) | !value as Lit
+}
+
+fn from_dimacs(lit: i32) -> Lit {
+    assert!(lit != 0, "0 is not a literal");
+    make_lit(lit.unsigned_abs() as usize - 1, lit > 0)
+}
+
+// Conflicts allowed before the first restart; later runs scale by the Luby sequence.
+const RESTART_BASE: u64 = 100;
+// Conflicts before the learnt clause limit first grows; the interval grows too.
+const LEARNT_ADJUST_BASE: f64 = 100.0;
+const VAR_DECAY: f64 = 0.95;
+const CLAUSE_DECAY: f64 = 0.999;
+
+struct Clause {
+    // The first two literals are the watched ones. For a reason clause, the
+    // first literal is the one it implied.
+    lits: Vec<Lit>,
+    learnt: bool,
+    activity: f64,
+    deleted: bool,
+}
+
+// A clause watching a literal. `blocker` is another literal of the clause:
+// when it is true the clause is satisfied and need not be visited.
+#[derive(Clone, Copy)]
+struct Watch {
+    clause: usize,
+    blocker: Lit,
+}
+
+#[derive(Default)]
+pub struct Solver {
+    clauses: Vec<Clause>,
+    learnts: Vec<usize>,
+    // For each literal, the clauses watching it, visited when it becomes false
+    watches: Vec<Vec<Watch>>,
+
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+
+    activity: Vec<f64>,
+    var_inc: f64,
+    clause_inc: f64,
+    order: VarOrder,
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+
+    max_learnts: f64,
+    learnt_adjust_interval: f64,
+    learnt_adjust_countdown: u64,
+    // Set once the clauses are known to be unsatisfiable
+    unsat: bool,
+    model: Vec<bool>,
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver { var_inc: 1.0, clause_inc: 1.0, ..Default::default() }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.assigns.len()
+    }
+
+    /// Makes sure variables `1..=n` exist, even if no clause mentions them.
+    pub fn reserve_vars(&mut self, n: usize) {
+        while self.assigns.len() < n {
+            let v = self.assigns.len();
+            self.assigns.push(None);
+            self.level.push(0);
+            self.reason.push(None);
+            self.activity.push(0.0);
+            self.phase.push(false);
+            self.seen.push(false);
+            self.watches.push(Vec::new());
+            self.watches.push(Vec::new());
+            self.order.insert(v, &self.activity);
+        }
+    }
+
+    /// Adds the clause `lits[0] | lits[1] | ...`. Can be called between solves.
+    pub fn add_clause(&mut self, lits: &[i32]) {
+        let mut lits: Vec<Lit> = lits.iter().map(|&lit| from_dimacs(lit)).collect();
+        if let Some(max) = lits.iter().map(|&lit| var(lit) + 1).max() {
+            self.reserve_vars(max);
+        }
+
+        lits.sort_unstable();
+        lits.dedup();
+        if lits.windows(2).any(|pair| pair[0] ^ 1 == pair[1]) {
+            return; // Contains both a variable and its negation
+        }
+        if lits.iter().any(|&lit| self.value(lit) == Some(true)) {
+            return; // Already satisfied at the top level
+        }
+        lits.retain(|&lit| self.value(lit).is_none());
+
+        match lits.len() {
+            0 => self.unsat = true,
+            1 => self.enqueue(lits[0], None),
+            _ => {
+                self.attach(lits, false);
+            }
+        }
+    }
+
+    /// Searches for an assignment satisfying every clause added so far.
+    pub fn solve(&mut self) -> bool {
+        if self.unsat {
+            return false;
+        }
+        if self.propagate().is_some() {
+            self.unsat = true;
+            return false;
+        }
+
+        self.max_learnts = (self.clauses.len() as f64 / 3.0).max(100.0);
+        self.learnt_adjust_interval = LEARNT_ADJUST_BASE;
+        self.learnt_adjust_countdown = LEARNT_ADJUST_BASE as u64;
+        let mut restarts = 0;
+        loop {
+            match self.search(luby(restarts) * RESTART_BASE) {
+                Some(result) => {
+                    if result {
+                        self.model = self.assigns.iter().map(|value| value.unwrap_or(false)).collect();
+                    }
+                    self.cancel_until(0);
+                    return result;
+                }
+                None => restarts += 1,
+            }
+        }
+    }
+
+    /// The satisfying assignment found by the last successful [`Solver::solve`],
+    /// indexed by variable number minus one.
+    pub fn model(&self) -> &[bool] {
+        &self.model
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[var(lit)].map(|value| value == (lit & 1 == 0))
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn attach(&mut self, lits: Vec<Lit>, learnt: bool) -> usize {
+        let index = self.clauses.len();
+        self.watches[lits[0] as usize].push(Watch { clause: index, blocker: lits[1] });
+        self.watches[lits[1] as usize].push(Watch { clause: index, blocker: lits[0] });
+        self.clauses.push(Clause { lits, learnt, activity: 0.0, deleted: false });
+        if learnt {
+            self.learnts.push(index);
+        }
+        index
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let v = var(lit);
+        self.assigns[v] = Some(lit & 1 == 0);
+        self.level[v] = self.decision_level();
+        self.reason[v] = reason;
+        self.trail.push(lit);
+    }
+
+    // Returns the conflicting clause, if any.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.qhead < self.trail.len() {
+            let false_lit = self.trail[self.qhead] ^ 1;
+            self.qhead += 1;
+
+            let mut watchers = std::mem::take(&mut self.watches[false_lit as usize]);
+            let mut kept = 0;
+            let mut conflict = None;
+            let mut i = 0;
+
+            while i < watchers.len() {
+                let watch = watchers[i];
+                i += 1;
+                if self.value(watch.blocker) == Some(true) {
+                    watchers[kept] = watch;
+                    kept += 1;
+                    continue;
+                }
+
+                let lits = &mut self.clauses[watch.clause].lits;
+                if lits[0] == false_lit {
+                    lits.swap(0, 1);
+                }
+                let first = lits[0];
+                let watch = Watch { clause: watch.clause, blocker: first };
+                if self.assigns[var(first)] == Some(first & 1 == 0) {
+                    watchers[kept] = watch;
+                    kept += 1;
+                    continue;
+                }
+
+                // Look for a new literal to watch instead of `false_lit`
+                let assigns = &self.assigns;
+                let replacement = (2..lits.len()).find(|&k| {
+                    let lit = lits[k];
+                    assigns[var(lit)] != Some(lit & 1 != 0)
+                });
+                if let Some(k) = replacement {
+                    lits.swap(1, k);
+                    self.watches[lits[1] as usize].push(watch);
+                    continue;
+                }
+
+                // The clause is unit or conflicting
+                watchers[kept] = watch;
+                kept += 1;
+                if self.value(first) == Some(false) {
+                    conflict = Some(watch.clause);
+                    while i < watchers.len() {
+                        watchers[kept] = watchers[i];
+                        kept += 1;
+                        i += 1;
+                    }
+                } else {
+                    self.enqueue(first, Some(watch.clause));
+                }
+            }
+
+            watchers.truncate(kept);
+            self.watches[false_lit as usize] = watchers;
+            if conflict.is_some() {
+                self.qhead = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    // Derives the first-UIP clause from a conflict. Returns it with the
+    // asserting literal first and the level to backjump to.
+    fn analyze(&mut self, mut conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![0];
+        let mut pending = 0;
+        let mut implied: Option<Lit> = None;
+        let mut index = self.trail.len();
+
+        loop {
+            self.bump_clause(conflict);
+            let skip = usize::from(implied.is_some());
+            for k in skip..self.clauses[conflict].lits.len() {
+                let lit = self.clauses[conflict].lits[k];
+                let v = var(lit);
+                if !self.seen[v] && self.level[v] > 0 {
+                    self.seen[v] = true;
+                    self.bump_var(v);
+                    if self.level[v] == self.decision_level() {
+                        pending += 1;
+                    } else {
+                        learnt.push(lit);
+                    }
+                }
+            }
+
+            // Walk back the trail to the next literal involved in the conflict
+            loop {
+                index -= 1;
+                if self.seen[var(self.trail[index])] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            self.seen[var(lit)] = false;
+            implied = Some(lit);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            conflict = self.reason[var(lit)].unwrap();
+        }
+        learnt[0] = implied.unwrap() ^ 1;
+
+        // Drop literals implied by the others
+        let candidates = learnt.split_off(1);
+        learnt.extend(candidates.iter().filter(|&&lit| !self.is_redundant(lit)));
+        for &lit in &candidates {
+            self.seen[var(lit)] = false;
+        }
+
+        // Watch the literal of the highest level after the asserting one
+        let mut backjump = 0;
+        if learnt.len() > 1 {
+            let max = (1..learnt.len()).max_by_key(|&k| self.level[var(learnt[k])]).unwrap();
+            learnt.swap(1, max);
+            backjump = self.level[var(learnt[1])];
+        }
+        (learnt, backjump)
+    }
+
+    // A learnt literal is redundant if its reason only involves other learnt
+    // literals or top-level facts.
+    fn is_redundant(&self, lit: Lit) -> bool {
+        match self.reason[var(lit)] {
+            Some(reason) => self.clauses[reason].lits[1..].iter().all(|&other| {
+                let v = var(other);
+                self.seen[v] || self.level[v] == 0
+            }),
+            None => false,
+        }
+    }
+
+    fn cancel_until(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_lim[level];
+        for k in (start..self.trail.len()).rev() {
+            let v = var(self.trail[k]);
+            self.phase[v] = self.assigns[v].unwrap();
+            self.assigns[v] = None;
+            self.reason[v] = None;
+            self.order.insert(v, &self.activity);
+        }
+        self.trail.truncate(start);
+        self.trail_lim.truncate(level);
+        self.qhead = start;
+    }
+
+    // Returns `Some(true)` when satisfiable, `Some(false)` when unsatisfiable,
+    // or `None` after `budget` conflicts to restart.
+    fn search(&mut self, budget: u64) -> Option<bool> {
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                conflicts += 1;
+                if self.decision_level() == 0 {
+                    self.unsat = true;
+                    return Some(false);
+                }
+
+                let (learnt, backjump) = self.analyze(conflict);
+                self.cancel_until(backjump);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let index = self.attach(learnt, true);
+                    self.bump_clause(index);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.var_inc /= VAR_DECAY;
+                self.clause_inc /= CLAUSE_DECAY;
+
+                self.learnt_adjust_countdown -= 1;
+                if self.learnt_adjust_countdown == 0 {
+                    self.learnt_adjust_interval *= 1.5;
+                    self.learnt_adjust_countdown = self.learnt_adjust_interval as u64;
+                    self.max_learnts *= 1.1;
+                }
+            } else {
+                if conflicts >= budget {
+                    self.cancel_until(0);
+                    return None;
+                }
+                if self.learnts.len() as f64 - self.trail.len() as f64 >= self.max_learnts {
+                    self.reduce_learnts();
+                }
+
+                match self.pick_branch() {
+                    Some(lit) => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(lit, None);
+                    }
+                    None => return Some(true),
+                }
+            }
+        }
+    }
+
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some(v) = self.order.pop(&self.activity) {
+            if self.assigns[v].is_none() {
+                return Some(make_lit(v, self.phase[v]));
+            }
+        }
+        None
+    }
+
+    fn bump_var(&mut self, v: usize) {
+        self.activity[v] += self.var_inc;
+        if self.activity[v] > 1e100 {
+            for activity in &mut self.activity {
+                *activity *= 1e-100;
+            }
+            self.var_inc *= 1e-100;
+        }
+        self.order.update(v, &self.activity);
+    }
+
+    fn bump_clause(&mut self, index: usize) {
+        let clause = &mut self.clauses[index];
+        if !clause.learnt {
+            return;
+        }
+        clause.activity += self.clause_inc;
+        if clause.activity > 1e20 {
+            for &learnt in &self.learnts {
+                self.clauses[learnt].activity *= 1e-20;
+            }
+            self.clause_inc *= 1e-20;
+        }
+    }
+
+    // Deletes the less active half of the learnt clauses, keeping binary
+    // clauses and those currently acting as a reason.
+    fn reduce_learnts(&mut self) {
+        let mut learnts = std::mem::take(&mut self.learnts);
+        learnts.sort_by(|&a, &b| self.clauses[a].activity.total_cmp(&self.clauses[b].activity));
+
+        let half = learnts.len() / 2;
+        let mut kept = Vec::with_capacity(learnts.len());
+        for (k, index) in learnts.into_iter().enumerate() {
+            let clause = &self.clauses[index];
+            let first = clause.lits[0];
+            let locked = self.reason[var(first)] == Some(index) && self.value(first) == Some(true);
+            if k < half && clause.lits.len() > 2 && !locked {
+                let clause = &mut self.clauses[index];
+                clause.deleted = true;
+                clause.lits = Vec::new();
+            } else {
+                kept.push(index);
+            }
+        }
+        self.learnts = kept;
+
+        let clauses = &self.clauses;
+        for watchers in &mut self.watches {
+            watchers.retain(|watch| !clauses[watch.clause].deleted);
+        }
+    }
+}
+
+// The Luby restart sequence 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ...
+fn luby(mut i: u64) -> u64 {
+    let mut size = 1;
+    let mut seq = 0;
+    while size < i + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != i {
+        size = (size - 1) >> 1;
+        seq -= 1;
+        i %= size;
+    }
+    1 << seq
+}
+
+// Max-heap of variables ordered by activity.
+#[derive(Default)]
+struct VarOrder {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>,
+}
+
+impl VarOrder {
+    fn insert(&mut self, v: usize, activity: &[f64]) {
+        if self.positions.len() <= v {
+            self.positions.resize(v + 1, None);
+        }
+        if self.positions[v].is_none() {
+            self.positions[v] = Some(self.heap.len());
+            self.heap.push(v);
+            self.sift_up(self.heap.len() - 1, activity);
+        }
+    }
+
+    // Restores the heap after the activity of `v` increased.
+    fn update(&mut self, v: usize, activity: &[f64]) {
+        if let Some(Some(pos)) = self.positions.get(v) {
+            self.sift_up(*pos, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn sift_up(&mut self, mut pos: usize, activity: &[f64]) {
+        let v = self.heap[pos];
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if activity[self.heap[parent]] >= activity[v] {
+                break;
+            }
+            self.heap[pos] = self.heap[parent];
+            self.positions[self.heap[pos]] = Some(pos);
+            pos = parent;
+        }
+        self.heap[pos] = v;
+        self.positions[v] = Some(pos);
+    }
+
+    fn sift_down(&mut self, mut pos: usize, activity: &[f64]) {
+        let v = self.heap[pos];
+        loop {
+            let left = 2 * pos + 1;
+            if left >= self.heap.len() {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < self.heap.len() && activity[self.heap[right]] > activity[self.heap[left]] {
+                right
+            } else {
+                left
+            };
+            if activity[self.heap[child]] <= activity[v] {
+                break;
+            }
+            self.heap[pos] = self.heap[child];
+            self.positions[self.heap[pos]] = Some(pos);
+            pos = child;
+        }
+        self.heap[pos] = v;
+        self.positions[v] = Some(pos);
+    }
+}
@@ -0,0 +1,296 @@
+//! Formulas synthesized from a truth table, the way back from ex04.
+//!
+//! The table is CSV or ex04's Markdown layout, as [`TruthTable`] writes them:
+//!
+//! ```text
+//! | A | B | = |          A,B,out
+//! |---|---|---|          0,0,1
+//! | 0 | 0 | 1 |          0,1,-
+//! | 0 | 1 | - |          1,1,1
+//! | 1 | 1 | 1 |
+//! ```
+//!
+//! The last column is the output, `1`, `0`, or `-` for a don't-care row
+//! that may go either way. The inputs are the columns before it whose
+//! headers are variable names, up to the first that is not, so the
+//! subformula columns of [`TruthTable::with_subformulas`] are skipped. Rows
+//! left out are `0`, as in the on-set of a PLA file. A row may be given more
+//! than once: a `0` or `1` wins over a `-`, in either order, but a `0` and a
+//! `1` conflict. In Markdown, `\|` is a `|` inside a cell.
+//!
+//! [`TruthTable`]: crate::truth::TruthTable
+//! [`TruthTable::with_subformulas`]: crate::truth::TruthTable::with_subformulas
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+
+use crate::formula::Formula;
+use crate::minimize::{self, Implicant};
+use crate::truth;
+use crate::var::Var;
+
+/// A function given by its true rows and don't-care rows over `vars`,
+/// numbered as in ex04, first variable as the most significant bit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spec {
+    pub vars: Vec<Var>,
+    pub minterms: Vec<u64>,
+    pub dont_cares: Vec<u64>,
+}
+
+/// Why a truth table could not be read. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    /// No header row.
+    Empty,
+    /// More than 63 input columns.
+    TooLarge { line: usize },
+    /// An input column named like an earlier one.
+    DuplicateVar { line: usize, name: String },
+    /// A row with more or fewer cells than the header.
+    BadRow { line: usize },
+    /// A cell other than `0` or `1`, or `-` for the output.
+    BadValue { line: usize, value: String },
+    /// A row given both `0` and `1`.
+    Conflict { line: usize, row: u64 },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecError::Empty => write!(f, "expected a header row"),
+            SpecError::TooLarge { line } => write!(f, "line {}: at most 63 inputs are supported", line),
+            SpecError::DuplicateVar { line, name } => write!(f, "line {}: duplicate input '{}'", line, name),
+            SpecError::BadRow { line } => write!(f, "line {}: the row does not have a cell per column", line),
+            SpecError::BadValue { line, value } => write!(f, "line {}: expected 0, 1 or -, not '{}'", line, value),
+            SpecError::Conflict { line, row } => write!(f, "line {}: row {} was already given another output", line, row),
+        }
+    }
+}
+
+impl Error for SpecError {}
+
+impl Spec {
+    /// Reads a truth table, as Markdown if its first line starts with `|`
+    /// and as CSV otherwise. Blank lines are skipped, and so are the lines
+    /// of a Markdown table that do not start with `|`.
+    ///
+    /// ```
+    /// use boole::synth::Spec;
+    ///
+    /// let spec = Spec::parse("A,B,F\n0,0,0\n0,1,1\n1,0,1\n1,1,-").unwrap();
+    /// assert_eq!(spec.minterms, [1, 2]);
+    /// assert_eq!(spec.dont_cares, [3]);
+    /// assert_eq!(spec.minimize().to_string(), "BA|");
+    /// ```
+    pub fn parse(input: &str) -> Result<Spec, SpecError> {
+        let lines = input.lines().enumerate().map(|(n, text)| (n + 1, text.trim())).filter(|(_, text)| !text.is_empty());
+        let markdown = lines.clone().next().is_some_and(|(_, text)| text.starts_with('|'));
+        let mut rows = lines.filter_map(|(line, text)| {
+            if !markdown {
+                return Some((line, split_csv(text)));
+            }
+            let cells = split_markdown(text)?;
+            // The rule under the header, `|---|:-:|`
+            let rule = cells.iter().all(|cell| !cell.is_empty() && cell.chars().all(|c| c == '-' || c == ':')) && cells.iter().any(|cell| cell.len() > 1);
+            (!rule).then_some((line, cells))
+        });
+
+        let (line, header) = rows.next().ok_or(SpecError::Empty)?;
+        let columns = header.len();
+        let mut spec = Spec::default();
+        for name in &header[..columns.saturating_sub(1)] {
+            let Ok(Formula::Var(var)) = crate::parse(name) else {
+                break;
+            };
+            if spec.vars.contains(&var) {
+                return Err(SpecError::DuplicateVar { line, name: name.clone() });
+            }
+            spec.vars.push(var);
+        }
+        let n = spec.vars.len();
+        if n > 63 {
+            return Err(SpecError::TooLarge { line });
+        }
+
+        let mut outputs: BTreeMap<u64, bool> = BTreeMap::new();
+        let mut dont_cares: BTreeSet<u64> = BTreeSet::new();
+        for (line, cells) in rows {
+            if cells.len() != columns {
+                return Err(SpecError::BadRow { line });
+            }
+            let bad = |cell: &String| SpecError::BadValue { line, value: cell.clone() };
+            let mut row = 0;
+            for cell in &cells[..n] {
+                let bit = match cell.as_str() {
+                    "0" => 0,
+                    "1" => 1,
+                    _ => return Err(bad(cell)),
+                };
+                row = row << 1 | bit;
+            }
+            let value = match cells[columns - 1].as_str() {
+                "0" => false,
+                "1" => true,
+                "-" => {
+                    dont_cares.insert(row);
+                    continue;
+                }
+                _ => return Err(bad(&cells[columns - 1])),
+            };
+            if outputs.insert(row, value).is_some_and(|previous| previous != value) {
+                return Err(SpecError::Conflict { line, row });
+            }
+        }
+        spec.minterms = outputs.iter().filter_map(|(&row, &value)| value.then_some(row)).collect();
+        spec.dont_cares = dont_cares.into_iter().filter(|row| !outputs.contains_key(row)).collect();
+        Ok(spec)
+    }
+
+    /// The sum of one full conjunction per true row, as
+    /// [`canonical::sum_of_minterms`](crate::canonical::sum_of_minterms)
+    /// builds it; don't-care rows are false.
+    pub fn sum_of_minterms(&self) -> Formula {
+        let terms: Vec<Implicant> = self.minterms.iter().map(|&row| Implicant::minterm(row)).collect();
+        minimize::to_formula(&self.vars, &terms)
+    }
+
+    /// A minimal sum of products, taking each don't-care row whichever way
+    /// makes it smaller.
+    pub fn minimize(&self) -> Formula {
+        let cover = minimize::minimal_cover(self.vars.len(), &self.minterms, &self.dont_cares);
+        minimize::to_formula(&self.vars, &cover)
+    }
+
+    /// Whether the formula has the table's output on every row that is not
+    /// a don't-care, taking variables outside the table as false.
+    pub fn realized_by(&self, f: &Formula) -> bool {
+        let table = truth::table(f, &self.vars);
+        let mut minterms = truth::ones(&table).filter(|row| self.dont_cares.binary_search(row).is_err());
+        self.minterms.iter().all(|&row| minterms.next() == Some(row)) && minterms.next().is_none()
+    }
+}
+
+// The cells of a Markdown table line, `None` if it does not start with
+// `|`; `\|` is a `|` inside a cell.
+fn split_markdown(text: &str) -> Option<Vec<String>> {
+    let mut cells = vec![String::new()];
+    let mut chars = text.strip_prefix('|')?.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                cells.last_mut().unwrap().push('|');
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    // Nothing after the closing `|`
+    if cells.len() > 1 && cells.last().unwrap().trim().is_empty() {
+        cells.pop();
+    }
+    Some(cells.into_iter().map(|cell| cell.trim().to_string()).collect())
+}
+
+// The fields of a CSV line, unquoted; `""` in a quoted field is one `"`.
+fn split_csv(text: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.into_iter().map(|field| field.trim().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical;
+    use crate::truth::TruthTable;
+
+    #[test]
+    fn round_trip() {
+        // Every format ex04 writes is read back to the same canonical and
+        // minimal forms, subformula columns skipped
+        for formula in ["AB&A!|", "AB^C>", "AB=CD=>", "A!", "AB|C&", "\"CLK_EN\" req&", "req_valid \"a,b\"&A|"] {
+            let f = crate::parse(formula).unwrap();
+            for table in [TruthTable::new(&f).unwrap(), TruthTable::with_subformulas(&f).unwrap()] {
+                for text in [table.to_markdown(), table.to_csv()] {
+                    let spec = Spec::parse(&text).unwrap();
+                    assert_eq!(spec.sum_of_minterms(), canonical::sum_of_minterms(&f), "Test failed for {}", formula);
+                    assert_eq!(spec.minimize(), minimize::minimize(&f), "Test failed for {}", formula);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bcd() {
+        // BCD digits of 5 and up; 10 to 15 are never inputs, so don't-cares
+        let mut input = String::from("| A | B | C | D | = |\n|---|---|---|---|---|\n");
+        for row in 0..16 {
+            let output = match row {
+                0..=4 => "0",
+                5..=9 => "1",
+                _ => "-",
+            };
+            input += &format!("| {} | {} | {} | {} | {} |\n", row >> 3, row >> 2 & 1, row >> 1 & 1, row & 1, output);
+        }
+        let spec = Spec::parse(&input).unwrap();
+        let (canonical, minimal) = (spec.sum_of_minterms(), spec.minimize());
+        assert_eq!(canonical.to_string(), "A!BC!D&&&A!BCD!&&&A!BCD&&&AB!C!D!&&&AB!C!D&&&||||");
+        assert_eq!(minimal.to_string(), "BD&BC&A||");
+        assert!(spec.realized_by(&canonical) && spec.realized_by(&minimal));
+        assert!(!spec.realized_by(&crate::parse("AB|").unwrap()));
+    }
+
+    #[test]
+    fn rows_and_names() {
+        // Rows left out are 0, quoted CSV names are read as variables
+        let spec = Spec::parse("\"valid\",ready,go\n1,1,1\n").unwrap();
+        assert_eq!(spec.sum_of_minterms().to_string(), "valid ready&");
+        assert_eq!(Spec::parse("| = |\n|---|\n| 1 |\n").unwrap().minimize().to_string(), "1");
+        // A don't-care gives way to a value for the same row, whichever comes first
+        for input in ["A,F\n1,-\n1,1\n", "A,F\n1,1\n1,-\n"] {
+            let spec = Spec::parse(input).unwrap();
+            assert_eq!((spec.minterms, spec.dont_cares), (vec![1], vec![]), "Test failed for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn escaped_bars() {
+        assert_eq!(split_markdown("| A | B | AB\\| | = |"), Some(vec!["A".to_string(), "B".to_string(), "AB|".to_string(), "=".to_string()]));
+        assert_eq!(split_markdown("A | B"), None);
+        let spec = Spec::parse("| A | B | AB\\| | = |\n|---|---|------|---|\n| 0 | 1 |  1   | 1 |\n").unwrap();
+        assert_eq!(spec.vars.len(), 2);
+        assert_eq!(spec.minterms, [1]);
+    }
+
+    #[test]
+    fn errors() {
+        let errors = [
+            ("", SpecError::Empty),
+            ("A,B,F\n0,1\n", SpecError::BadRow { line: 2 }),
+            ("A,B,F\n0,2,1\n", SpecError::BadValue { line: 2, value: "2".to_string() }),
+            ("A,B,F\n0,-,1\n", SpecError::BadValue { line: 2, value: "-".to_string() }),
+            ("A,A,F\n", SpecError::DuplicateVar { line: 1, name: "A".to_string() }),
+            ("| A | = |\n|---|---|\n| 1 | 1 |\n| 1 | 0 |\n", SpecError::Conflict { line: 4, row: 1 }),
+        ];
+        for (input, expected) in errors {
+            assert_eq!(Spec::parse(input), Err(expected), "Test failed for {:?}", input);
+        }
+        let names: Vec<String> = (0..64).map(|i| format!("x{}", i)).collect();
+        assert_eq!(Spec::parse(&format!("{},F\n", names.join(","))), Err(SpecError::TooLarge { line: 1 }));
+    }
+}
@@ -0,0 +1,412 @@
+//! Truth tables computed 64 rows at a time.
+//!
+//! Rows are numbered as in ex04's table, first variable as the most
+//! significant bit. Word `w` of a table holds rows `64 * w` to `64 * w + 63`,
+//! row `64 * w + k` in bit `k`. In every word, the last six variables take
+//! the same fixed patterns (the last one alternates `0101...`), and the others
+//! are constant, read off the word's number. Each word is one run of the
+//! formula compiled to a [`Program`], with bitwise operations.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::formula::Formula;
+use crate::infix;
+use crate::program::Program;
+use crate::var::Var;
+
+/// The values of the variable at bit `bit` of the row number (0 for the last
+/// variable) over the 64 rows of a word, for the 6 bits inside a word.
+const PATTERNS: [u64; 6] = [
+    0xaaaa_aaaa_aaaa_aaaa,
+    0xcccc_cccc_cccc_cccc,
+    0xf0f0_f0f0_f0f0_f0f0,
+    0xff00_ff00_ff00_ff00,
+    0xffff_0000_ffff_0000,
+    0xffff_ffff_0000_0000,
+];
+
+/// The number of words of a table over `num_vars` variables.
+pub fn words(num_vars: usize) -> usize {
+    1 << num_vars.saturating_sub(6)
+}
+
+/// The values of variable `i` of `num_vars` on the rows of word `word`.
+pub fn var_mask(num_vars: usize, i: usize, word: usize) -> u64 {
+    let bit = num_vars - 1 - i;
+    match PATTERNS.get(bit) {
+        Some(&pattern) => pattern,
+        None if word >> (bit - 6) & 1 == 1 => !0,
+        None => 0,
+    }
+}
+
+/// The bits of the rows that exist: all of them but with fewer than 6
+/// variables.
+pub fn used_lanes(num_vars: usize) -> u64 {
+    match num_vars {
+        0..=5 => (1 << (1 << num_vars)) - 1,
+        _ => !0,
+    }
+}
+
+/// The words of the truth table of a program over its slots, computed as
+/// they are read. Bits past the last row are 0.
+pub struct Words<'a> {
+    program: &'a Program,
+    slots: Vec<u64>,
+    stack: Vec<u64>,
+    word: usize,
+}
+
+impl<'a> Words<'a> {
+    /// The words of a program of at most 63 slots.
+    pub fn new(program: &'a Program) -> Words<'a> {
+        let n = program.vars().len();
+        assert!(n < 64, "a truth table over {} variables has too many rows", n);
+        let slots = (0..n).map(|i| var_mask(n, i, 0)).collect();
+        Words { program, slots, stack: program.stack(), word: 0 }
+    }
+}
+
+impl Iterator for Words<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let n = self.slots.len();
+        if self.word == words(n) {
+            return None;
+        }
+        // Variables inside a word keep their pattern, the others are updated
+        for i in 0..n.saturating_sub(6) {
+            self.slots[i] = var_mask(n, i, self.word);
+        }
+        self.word += 1;
+        Some(self.program.eval_lanes(&self.slots, &mut self.stack) & used_lanes(n))
+    }
+}
+
+/// The truth table of the formula over `vars`, one bit per row. Bits past
+/// the last row are 0.
+///
+/// ```
+/// let f = boole::parse("AB>").unwrap();
+/// let vars = ['A'.into(), 'B'.into()];
+/// // Rows 00, 01, 11 are true, row 10 is false
+/// assert_eq!(boole::truth::table(&f, &vars), [0b1011]);
+/// ```
+pub fn table(f: &Formula, vars: &[Var]) -> Vec<u64> {
+    Words::new(&Program::compile(f, vars)).collect()
+}
+
+/// The value of row `row` of a table.
+pub fn get(table: &[u64], row: u64) -> bool {
+    table[(row / 64) as usize] >> (row % 64) & 1 == 1
+}
+
+/// The true rows of a table, in order.
+pub fn ones(table: &[u64]) -> impl Iterator<Item = u64> + '_ {
+    table.iter().enumerate().flat_map(|(w, &word)| {
+        // Each step clears the lowest set bit
+        let bits = std::iter::successors((word != 0).then_some(word), |&rest| (rest & (rest - 1) != 0).then(|| rest & (rest - 1)));
+        bits.map(move |rest| 64 * w as u64 + rest.trailing_zeros() as u64)
+    })
+}
+
+/// The number of true rows of a table.
+pub fn count(table: &[u64]) -> u64 {
+    table.iter().map(|word| word.count_ones() as u64).sum()
+}
+
+/// The most variables of a [`TruthTable`], whose columns take 2^n bits each.
+pub const MAX_VARS: usize = 32;
+
+/// Why a [`TruthTable`] could not be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// More than [`MAX_VARS`] variables.
+    TooManyVars { vars: usize },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableError::TooManyVars { vars } => write!(f, "a truth table holds at most {} variables, not {}", MAX_VARS, vars),
+        }
+    }
+}
+
+impl Error for TableError {}
+
+/// The truth table of a formula as a value, optionally with a column per
+/// subformula, to print as Markdown, CSV, JSON or LaTeX.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruthTable {
+    vars: Vec<Var>,
+    // The subformulas, then the formula, each with its values
+    columns: Vec<(Formula, Vec<u64>)>,
+}
+
+impl TruthTable {
+    /// The table over the formula's variables, in name order. Fails past
+    /// [`MAX_VARS`] variables.
+    pub fn new(f: &Formula) -> Result<TruthTable, TableError> {
+        let vars: Vec<Var> = f.vars().into_iter().collect();
+        if vars.len() > MAX_VARS {
+            return Err(TableError::TooManyVars { vars: vars.len() });
+        }
+        Ok(TruthTable { columns: vec![(f.clone(), table(f, &vars))], vars })
+    }
+
+    /// The table with a column before the result for each distinct compound
+    /// subformula, innermost first, in the order of the RPN.
+    pub fn with_subformulas(f: &Formula) -> Result<TruthTable, TableError> {
+        let mut truth_table = TruthTable::new(f)?;
+        let mut subformulas = Vec::new();
+        collect_subformulas(f, &mut subformulas);
+        subformulas.pop();
+        let columns = subformulas.into_iter().map(|sub| {
+            let values = table(&sub, &truth_table.vars);
+            (sub, values)
+        });
+        truth_table.columns.splice(..0, columns);
+        Ok(truth_table)
+    }
+
+    pub fn vars(&self) -> &[Var] {
+        &self.vars
+    }
+
+    /// The formula of the last column.
+    pub fn formula(&self) -> &Formula {
+        &self.columns.last().unwrap().0
+    }
+
+    /// The subformulas of the other columns.
+    pub fn subformulas(&self) -> impl Iterator<Item = &Formula> {
+        self.columns[..self.columns.len() - 1].iter().map(|(f, _)| f)
+    }
+
+    pub fn rows(&self) -> u64 {
+        1 << self.vars.len()
+    }
+
+    /// The formula's value in a row.
+    pub fn value(&self, row: u64) -> bool {
+        get(&self.columns.last().unwrap().1, row)
+    }
+
+    // Headers and 0/1 cells of every column, variables first, with `label`
+    // writing the formula columns.
+    fn cells(&self, label: impl Fn(&Formula) -> String) -> (Vec<String>, Vec<Vec<u8>>) {
+        let mut header: Vec<String> = self.vars.iter().map(|var| var.token().to_string()).collect();
+        header.extend(self.columns.iter().map(|(f, _)| label(f)));
+        let rows = (0..self.rows())
+            .map(|row| {
+                let n = self.vars.len();
+                let inputs = (0..n).map(|i| (row >> (n - 1 - i) & 1) as u8);
+                inputs.chain(self.columns.iter().map(|(_, values)| get(values, row) as u8)).collect()
+            })
+            .collect();
+        (header, rows)
+    }
+
+    /// The table as ex04 prints it, each column as wide as its header,
+    /// subformulas in RPN and the result under `=`. A `|` in a header is
+    /// written `\|`, so it does not end the cell.
+    ///
+    /// ```text
+    /// | A | B | = |
+    /// |---|---|---|
+    /// | 0 | 0 | 1 |
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let result = self.formula();
+        let (header, rows) = self.cells(|f| if f == result { "=".to_string() } else { f.to_string() });
+        let header: Vec<String> = header.iter().map(|label| label.replace('|', "\\|")).collect();
+        let widths: Vec<usize> = header.iter().map(|label| label.chars().count()).collect();
+        let mut out = String::from("|");
+        for label in &header {
+            out += &format!(" {} |", label);
+        }
+        out += "\n|";
+        for width in &widths {
+            out += &format!("{}|", "-".repeat(width + 2));
+        }
+        out += "\n";
+        for row in rows {
+            out += "|";
+            for (value, &width) in row.iter().zip(&widths) {
+                out += &format!(" {:^width$} |", value);
+            }
+            out += "\n";
+        }
+        out
+    }
+
+    /// The table as CSV, formulas in RPN in the header, quoted when needed.
+    pub fn to_csv(&self) -> String {
+        let (header, rows) = self.cells(|f| f.to_string());
+        let quote = |field: &String| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        };
+        let mut out = header.iter().map(quote).collect::<Vec<_>>().join(",") + "\n";
+        for row in rows {
+            out += &row.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(",");
+            out += "\n";
+        }
+        out
+    }
+
+    /// The table as JSON: the formula in RPN, the column headers, and one
+    /// array of 0s and 1s per row.
+    ///
+    /// ```text
+    /// {"formula": "AB>", "columns": ["A", "B", "AB>"], "rows": [[0, 0, 1], ...]}
+    /// ```
+    pub fn to_json(&self) -> String {
+        let (header, rows) = self.cells(|f| f.to_string());
+        let string = |text: &str| {
+            let mut out = String::from("\"");
+            for c in text.chars() {
+                match c {
+                    '"' => out += "\\\"",
+                    '\\' => out += "\\\\",
+                    c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+                    c => out.push(c),
+                }
+            }
+            out + "\""
+        };
+        let columns: Vec<String> = header.iter().map(|label| string(label)).collect();
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|row| format!("[{}]", row.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(", ")))
+            .collect();
+        format!(
+            "{{\"formula\": {}, \"columns\": [{}], \"rows\": [{}]}}\n",
+            string(&self.formula().to_string()),
+            columns.join(", "),
+            rows.join(", ")
+        )
+    }
+
+    /// The table as a LaTeX `tabular`, formulas in infix math, rules
+    /// between the variables, the subformulas and the result.
+    pub fn to_latex(&self) -> String {
+        let (header, rows) = self.cells(infix::to_latex);
+        let n = self.vars.len();
+        let subformulas = self.columns.len() - 1;
+        let mut spec = "c".repeat(n);
+        if subformulas > 0 {
+            spec += "|";
+            spec += &"c".repeat(subformulas);
+        }
+        spec += "|c";
+
+        let mut out = format!("\\begin{{tabular}}{{{}}}\n", spec.trim_start_matches('|'));
+        let header: Vec<String> = header
+            .iter()
+            .enumerate()
+            .map(|(i, label)| if i < n { format!("${}$", infix::to_latex(&Formula::Var(self.vars[i]))) } else { format!("${}$", label) })
+            .collect();
+        out += &format!("{} \\\\\n\\hline\n", header.join(" & "));
+        for row in rows {
+            out += &format!("{} \\\\\n", row.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(" & "));
+        }
+        out += "\\end{tabular}\n";
+        out
+    }
+}
+
+// Compound subformulas in RPN order, each once.
+fn collect_subformulas(f: &Formula, out: &mut Vec<Formula>) {
+    match f {
+        Formula::Const(_) | Formula::Var(_) => return,
+        Formula::Not(a) => collect_subformulas(a, out),
+        _ => {
+            let (a, b) = f.operands().unwrap();
+            collect_subformulas(a, out);
+            collect_subformulas(b, out);
+        }
+    }
+    if !out.contains(f) {
+        out.push(f.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula;
+
+    #[test]
+    fn words_agree_with_eval() {
+        // Below and above the 6 variables that fit in a word
+        for formula in ["A", "AB^C>", "AB&CD&|EF&|", "x0 x1 x2 x3 x4 x5 x6 x7 ^^^^^^^!", "AB=CD=>EF|G!&H>^"] {
+            let f = crate::parse(formula).unwrap();
+            let vars: Vec<Var> = f.vars().into_iter().collect();
+            let table = table(&f, &vars);
+            for i in 0..1u64 << vars.len() {
+                assert_eq!(get(&table, i), f.eval(&formula::row(&vars, i)), "Row {} differs for {}", i, formula);
+            }
+            assert_eq!(ones(&table).count() as u64, count(&table));
+        }
+    }
+
+    #[test]
+    fn twenty_two_variables() {
+        // (x0 & x1) | (x2 & x3) | ... | (x20 & x21) is false when no pair is
+        // all true, in 3^11 rows of 4^11
+        let pairs = (1..11).fold(String::from("x0 x1&"), |acc, i| format!("{} x{} x{}&|", acc, 2 * i, 2 * i + 1));
+        let f = crate::parse(&pairs).unwrap();
+        let vars: Vec<Var> = f.vars().into_iter().collect();
+        assert_eq!(count(&table(&f, &vars)), 4u64.pow(11) - 3u64.pow(11));
+    }
+
+    #[test]
+    fn formats() {
+        // A column per subformula, in each format
+        let table = TruthTable::with_subformulas(&crate::parse("AB&A!|").unwrap()).unwrap();
+        assert_eq!(
+            table.to_markdown(),
+            "| A | B | AB& | A! | = |\n|---|---|-----|----|---|\n| 0 | 0 |  0  | 1  | 1 |\n| 0 | 1 |  0  | 1  | 1 |\n| 1 | 0 |  0  | 0  | 0 |\n| 1 | 1 |  1  | 0  | 1 |\n"
+        );
+        assert_eq!(table.to_csv(), "A,B,AB&,A!,AB&A!|\n0,0,0,1,1\n0,1,0,1,1\n1,0,0,0,0\n1,1,1,0,1\n");
+        assert_eq!(
+            table.to_json(),
+            "{\"formula\": \"AB&A!|\", \"columns\": [\"A\", \"B\", \"AB&\", \"A!\", \"AB&A!|\"], \"rows\": [[0, 0, 0, 1, 1], [0, 1, 0, 1, 1], [1, 0, 0, 0, 0], [1, 1, 1, 0, 1]]}\n"
+        );
+        assert_eq!(
+            table.to_latex(),
+            "\\begin{tabular}{cc|cc|c}\n$A$ & $B$ & $A \\land B$ & $\\lnot A$ & $A \\land B \\lor \\lnot A$ \\\\\n\\hline\n0 & 0 & 0 & 1 & 1 \\\\\n0 & 1 & 0 & 1 & 1 \\\\\n1 & 0 & 0 & 0 & 0 \\\\\n1 & 1 & 1 & 0 & 1 \\\\\n\\end{tabular}\n"
+        );
+        assert!(!table.value(2) && table.value(3));
+    }
+
+    #[test]
+    fn names() {
+        // Quoted in CSV when they have a comma, with an underscore escaped in LaTeX
+        let table = TruthTable::new(&crate::parse("req_valid \"a,b\"&").unwrap()).unwrap();
+        assert_eq!(table.to_csv().lines().next(), Some("\"\"\"a,b\"\"\",req_valid,\"req_valid \"\"a,b\"\"&\""));
+        assert!(table.to_latex().contains("$\\mathit{req\\_valid}$"));
+        // Variable headers are written as in a formula, quoted if they need it
+        let table = TruthTable::new(&crate::parse("\"CLK_EN\" req&").unwrap()).unwrap();
+        assert_eq!(table.to_markdown().lines().next(), Some("| \"CLK_EN\" | req | = |"));
+        // A `|` inside a Markdown cell is escaped
+        let table = TruthTable::with_subformulas(&crate::parse("AB|C&").unwrap()).unwrap();
+        assert_eq!(table.to_markdown().lines().take(3).collect::<Vec<_>>(), ["| A | B | C | AB\\| | = |", "|---|---|---|------|---|", "| 0 | 0 | 0 |  0   | 0 |"]);
+    }
+
+    #[test]
+    fn too_many_vars() {
+        let names: Vec<String> = (0..64).map(|i| format!("x{}", i)).collect();
+        let f = crate::parse(&names.join(" | ")).unwrap();
+        assert_eq!(TruthTable::new(&f), Err(TableError::TooManyVars { vars: 64 }));
+        assert_eq!(TruthTable::with_subformulas(&f), Err(TableError::TooManyVars { vars: 64 }));
+    }
+}
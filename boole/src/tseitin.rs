@@ -0,0 +1,154 @@
+//! CNF encodings with auxiliary variables, linear in the size of the formula.
+//!
+//! Distributing `|` over `&` is exponential on formulas like
+//! `AB&CD&|EF&|...`. Instead, each operator gets a fresh variable standing
+//! for its value, constrained by a few clauses relating it to its operands.
+//! The result is equisatisfiable with the formula rather than equivalent:
+//! it has extra variables, and restricting one of its models to the
+//! formula's variables gives a model of the formula.
+
+use std::collections::BTreeSet;
+
+use crate::cnf::{Clause, Cnf, Lit};
+use crate::formula::Formula;
+use crate::var::Var;
+
+/// Tseitin encoding: each auxiliary variable is equivalent to its
+/// subformula, so every model of the formula extends to exactly one model
+/// of the clauses and model counts are preserved.
+pub fn tseitin(f: &Formula) -> Cnf {
+    Encoder::new(f, false).run(f)
+}
+
+/// Plaisted–Greenbaum encoding: each auxiliary variable only implies its
+/// subformula (or is implied by it) in the direction the subformula is used,
+/// which takes about half the clauses of [`tseitin`]. Models of the formula
+/// may extend to several models of the clauses.
+pub fn plaisted_greenbaum(f: &Formula) -> Cnf {
+    Encoder::new(f, true).run(f)
+}
+
+/// Which values of a subformula matter: `Positive` under an even number of
+/// negations, `Negative` under an odd number, `Both` under `^` or `=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    fn flip(self) -> Polarity {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    fn positive(self) -> bool {
+        self != Polarity::Negative
+    }
+
+    fn negative(self) -> bool {
+        self != Polarity::Positive
+    }
+}
+
+struct Encoder {
+    clauses: Vec<Clause>,
+    // Names of the formula, which auxiliary variables must not reuse
+    taken: BTreeSet<Var>,
+    next: usize,
+    polarity_aware: bool,
+    // The variable forced true that stands for constants, once needed
+    truth: Option<Var>,
+}
+
+impl Encoder {
+    fn new(f: &Formula, polarity_aware: bool) -> Encoder {
+        Encoder { clauses: Vec::new(), taken: f.vars(), next: 0, polarity_aware, truth: None }
+    }
+
+    fn run(mut self, f: &Formula) -> Cnf {
+        let root = self.encode(f, Polarity::Positive);
+        self.clauses.push(vec![root]);
+        Cnf { clauses: self.clauses }
+    }
+
+    // Auxiliary variables are named `_t1`, `_t2`, ..., skipping names the
+    // formula already uses.
+    fn fresh(&mut self) -> Var {
+        loop {
+            self.next += 1;
+            let var = Var::new(&format!("_t{}", self.next));
+            if !self.taken.contains(&var) {
+                return var;
+            }
+        }
+    }
+
+    // Returns a literal with the value of `f` in every model of the clauses,
+    // or, for Plaisted–Greenbaum, one that implies `f` where `f` is
+    // positive and is implied by it where `f` is negative.
+    fn encode(&mut self, f: &Formula, polarity: Polarity) -> Lit {
+        let polarity = if self.polarity_aware { polarity } else { Polarity::Both };
+        let (a, b) = match f {
+            Formula::Const(value) => {
+                let truth = self.truth();
+                return if *value { Lit::pos(truth) } else { Lit::neg(truth) };
+            }
+            Formula::Var(var) => return Lit::pos(*var),
+            Formula::Not(a) => return !self.encode(a, polarity.flip()),
+            _ => f.operands().unwrap(),
+        };
+
+        let x = Lit::pos(self.fresh());
+        match f {
+            // x <-> A & B
+            Formula::And(..) => {
+                let (a, b) = (self.encode(a, polarity), self.encode(b, polarity));
+                self.emit(polarity.positive(), vec![vec![!x, a], vec![!x, b]]);
+                self.emit(polarity.negative(), vec![vec![x, !a, !b]]);
+            }
+            // x <-> A | B, and A > B is !A | B
+            Formula::Or(..) | Formula::Imply(..) => {
+                let a = match f {
+                    Formula::Imply(..) => !self.encode(a, polarity.flip()),
+                    _ => self.encode(a, polarity),
+                };
+                let b = self.encode(b, polarity);
+                self.emit(polarity.positive(), vec![vec![!x, a, b]]);
+                self.emit(polarity.negative(), vec![vec![x, !a], vec![x, !b]]);
+            }
+            // x <-> A ^ B, and A = B is A ^ !B
+            Formula::Xor(..) | Formula::Equiv(..) => {
+                let a = self.encode(a, Polarity::Both);
+                let b = match f {
+                    Formula::Equiv(..) => !self.encode(b, Polarity::Both),
+                    _ => self.encode(b, Polarity::Both),
+                };
+                self.emit(polarity.positive(), vec![vec![!x, a, b], vec![!x, !a, !b]]);
+                self.emit(polarity.negative(), vec![vec![x, !a, b], vec![x, a, !b]]);
+            }
+            _ => unreachable!("not a binary operator"),
+        }
+        x
+    }
+
+    fn emit(&mut self, wanted: bool, clauses: Vec<Clause>) {
+        if wanted {
+            self.clauses.extend(clauses);
+        }
+    }
+
+    fn truth(&mut self) -> Var {
+        if let Some(truth) = self.truth {
+            return truth;
+        }
+        let truth = self.fresh();
+        self.clauses.push(vec![Lit::pos(truth)]);
+        self.truth = Some(truth);
+        truth
+    }
+}
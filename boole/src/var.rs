@@ -0,0 +1,143 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::ParseError;
+
+/// An interned variable name.
+///
+/// Every name is stored once for the lifetime of the program, so a `Var` is
+/// a copyable pointer: equality and hashing compare addresses, while ordering
+/// compares names so sorted collections list variables alphabetically.
+///
+/// In formulas, a single uppercase letter is a variable on its own (`AB&` is
+/// `A & B`). Longer names start with a lowercase letter or `_` and continue
+/// with letters, digits, `_`, `[`, `]` and `.`, like `req_valid` or
+/// `grant[3]`. Any other name is written in double quotes, like `"CLK_EN"`.
+#[derive(Clone, Copy)]
+pub struct Var(&'static str);
+
+impl Var {
+    pub fn new(name: &str) -> Var {
+        static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut names = NAMES.get_or_init(Default::default).lock().unwrap();
+
+        match names.get(name) {
+            Some(interned) => Var(interned),
+            None => {
+                let interned: &'static str = Box::leak(name.into());
+                names.insert(interned);
+                Var(interned)
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+
+    /// The name as it must be written in a formula, quoted if needed.
+    pub fn token(self) -> Cow<'static, str> {
+        if is_letter(self.0) || is_identifier(self.0) {
+            Cow::Borrowed(self.0)
+        } else {
+            Cow::Owned(format!("\"{}\"", self.0))
+        }
+    }
+
+    /// Whether the token of this variable would merge with a following
+    /// letter or digit, so RPN output needs a space after it.
+    pub(crate) fn is_open_ended(self) -> bool {
+        !is_letter(self.0) && is_identifier(self.0)
+    }
+}
+
+impl From<char> for Var {
+    fn from(name: char) -> Var {
+        Var::new(name.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl From<&str> for Var {
+    fn from(name: &str) -> Var {
+        Var::new(name)
+    }
+}
+
+impl PartialEq for Var {
+    fn eq(&self, other: &Var) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Var {}
+
+impl Hash for Var {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
+
+impl PartialOrd for Var {
+    fn partial_cmp(&self, other: &Var) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Var {
+    fn cmp(&self, other: &Var) -> std::cmp::Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl fmt::Debug for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+fn is_letter(name: &str) -> bool {
+    name.len() == 1 && name.as_bytes()[0].is_ascii_uppercase()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_identifier_start) && chars.all(is_identifier_char)
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_ascii_lowercase() || ch == '_'
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '[' | ']' | '.')
+}
+
+/// Reads the variable starting at byte `offset` of `formula`, returning it
+/// with the length of its token. `None` if no variable starts there.
+pub(crate) fn scan(formula: &str, offset: usize) -> Option<Result<(Var, usize), ParseError>> {
+    let rest = &formula[offset..];
+    let first = rest.chars().next()?;
+
+    if first.is_ascii_uppercase() {
+        Some(Ok((Var::from(first), 1)))
+    } else if is_identifier_start(first) {
+        let len = rest.find(|c| !is_identifier_char(c)).unwrap_or(rest.len());
+        Some(Ok((Var::new(&rest[..len]), len)))
+    } else if first == '"' {
+        Some(match rest[1..].find('"') {
+            Some(0) | None => Err(ParseError::BadQuotedName { offset }),
+            Some(len) => Ok((Var::new(&rest[1..=len]), len + 2)),
+        })
+    } else {
+        None
+    }
+}
@@ -0,0 +1,261 @@
+// A small line editor for the REPL: cursor movement, history and tab
+// completion, with the terminal switched to raw mode through `stty`
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+const MAX_HISTORY: usize = 1000;
+
+pub struct Editor {
+    history: Vec<String>,
+}
+
+// Restores the terminal settings saved by `stty -g` when dropped, even if
+// reading the line fails.
+struct RawMode(String);
+
+impl RawMode {
+    fn enter() -> io::Result<RawMode> {
+        let saved = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output()?;
+        let saved = String::from_utf8_lossy(&saved.stdout).trim().to_string();
+        // No line buffering, no echo, and Ctrl-C read as a key
+        Command::new("stty").args(["-icanon", "-echo", "-isig", "min", "1"]).stdin(Stdio::inherit()).status()?;
+        Ok(RawMode(saved))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty").arg(&self.0).stdin(Stdio::inherit()).status();
+    }
+}
+
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Tab,
+    Interrupt,
+    Eof,
+    KillStart,
+    KillEnd,
+    Other,
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    Ok(match input.read(&mut byte)? {
+        0 => None,
+        _ => Some(byte[0]),
+    })
+}
+
+fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    Ok(Some(match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        b'\t' => Key::Tab,
+        0x01 => Key::Home,
+        0x05 => Key::End,
+        0x02 => Key::Left,
+        0x06 => Key::Right,
+        0x10 => Key::Up,
+        0x0e => Key::Down,
+        0x03 => Key::Interrupt,
+        0x04 => Key::Eof,
+        0x15 => Key::KillStart,
+        0x0b => Key::KillEnd,
+        // Escape sequences: `ESC [ A` for up, `ESC [ 3 ~` for delete...
+        0x1b => match (read_byte(input)?, read_byte(input)?) {
+            (Some(b'[' | b'O'), Some(b'A')) => Key::Up,
+            (Some(b'[' | b'O'), Some(b'B')) => Key::Down,
+            (Some(b'[' | b'O'), Some(b'C')) => Key::Right,
+            (Some(b'[' | b'O'), Some(b'D')) => Key::Left,
+            (Some(b'[' | b'O'), Some(b'H')) => Key::Home,
+            (Some(b'[' | b'O'), Some(b'F')) => Key::End,
+            (Some(b'['), Some(digit @ b'0'..=b'9')) => match (digit, read_byte(input)?) {
+                (b'3', Some(b'~')) => Key::Delete,
+                (b'1' | b'7', Some(b'~')) => Key::Home,
+                (b'4' | b'8', Some(b'~')) => Key::End,
+                _ => Key::Other,
+            },
+            _ => Key::Other,
+        },
+        byte if byte < 0x20 => Key::Other,
+        byte => {
+            // The continuation bytes of a UTF-8 character
+            let len = byte.leading_ones().max(1) as usize;
+            let mut bytes = vec![byte];
+            for _ in 1..len {
+                bytes.extend(read_byte(input)?);
+            }
+            match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+                Some(c) => Key::Char(c),
+                None => Key::Other,
+            }
+        }
+    }))
+}
+
+// Characters of a variable name or command, the unit of completion.
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '[' | ']' | ':')
+}
+
+impl Editor {
+    /// An editor whose history starts with the lines of `history`.
+    pub fn new(history: &str) -> Editor {
+        let history: Vec<String> = history.lines().filter(|line| !line.trim().is_empty()).map(String::from).collect();
+        let skip = history.len().saturating_sub(MAX_HISTORY);
+        Editor { history: history[skip..].to_vec() }
+    }
+
+    /// Adds a line to the history, unless it is blank or repeats the last.
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.history.push(line.to_string());
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    /// The history, one line each, oldest first.
+    pub fn history(&self) -> String {
+        self.history.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    /// Reads a line, `None` at end of input. `complete` lists the names
+    /// starting with the word before the cursor.
+    pub fn read_line(&mut self, prompt: &str, complete: &dyn Fn(&str) -> Vec<String>) -> io::Result<Option<String>> {
+        let _raw = RawMode::enter()?;
+        let mut input = io::stdin().lock();
+        let mut out = io::stdout().lock();
+        let mut line: Vec<char> = Vec::new();
+        let mut cursor = 0;
+        // Position in the history, and the line being written before moving
+        let mut entry = self.history.len();
+        let mut draft: Vec<char> = Vec::new();
+
+        redraw(&mut out, prompt, &line, cursor)?;
+        loop {
+            let Some(key) = read_key(&mut input)? else {
+                writeln!(out)?;
+                return Ok(None);
+            };
+            match key {
+                Key::Char(c) => {
+                    line.insert(cursor, c);
+                    cursor += 1;
+                }
+                Key::Enter => {
+                    writeln!(out)?;
+                    return Ok(Some(line.into_iter().collect()));
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                Key::Delete if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::Eof if line.is_empty() => {
+                    writeln!(out)?;
+                    return Ok(None);
+                }
+                Key::Eof if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::Left => cursor = cursor.saturating_sub(1),
+                Key::Right => cursor = (cursor + 1).min(line.len()),
+                Key::Home => cursor = 0,
+                Key::End => cursor = line.len(),
+                Key::KillStart => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                }
+                Key::KillEnd => line.truncate(cursor),
+                Key::Up | Key::Down => {
+                    let up = matches!(key, Key::Up);
+                    if (up && entry == 0) || (!up && entry == self.history.len()) {
+                        write!(out, "\x07")?;
+                        out.flush()?;
+                        continue;
+                    }
+                    if entry == self.history.len() {
+                        draft = line.clone();
+                    }
+                    entry = if up { entry - 1 } else { entry + 1 };
+                    line = match self.history.get(entry) {
+                        Some(previous) => previous.chars().collect(),
+                        None => draft.clone(),
+                    };
+                    cursor = line.len();
+                }
+                Key::Tab => {
+                    let start = line[..cursor].iter().rposition(|&c| !is_word(c)).map_or(0, |i| i + 1);
+                    let word: String = line[start..cursor].iter().collect();
+                    let names = complete(&word);
+                    let common = common_prefix(&names);
+                    if names.is_empty() {
+                        write!(out, "\x07")?;
+                    } else if common.chars().count() > word.chars().count() || names.len() == 1 {
+                        let mut rest: Vec<char> = common.chars().skip(word.chars().count()).collect();
+                        if names.len() == 1 && names[0].starts_with(':') {
+                            rest.push(' ');
+                        }
+                        let len = rest.len();
+                        line.splice(cursor..cursor, rest);
+                        cursor += len;
+                    } else {
+                        // Several ways to go on: list them under the line
+                        write!(out, "\r\n{}\r\n", names.join("  "))?;
+                    }
+                }
+                Key::Interrupt => {
+                    writeln!(out, "^C")?;
+                    line.clear();
+                    cursor = 0;
+                    entry = self.history.len();
+                }
+                _ => {}
+            }
+            redraw(&mut out, prompt, &line, cursor)?;
+        }
+    }
+}
+
+fn redraw(out: &mut impl Write, prompt: &str, line: &[char], cursor: usize) -> io::Result<()> {
+    let text: String = line.iter().collect();
+    write!(out, "\r{}{}\x1b[K", prompt, text)?;
+    if cursor < line.len() {
+        write!(out, "\x1b[{}D", line.len() - cursor)?;
+    }
+    out.flush()
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let Some(first) = names.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for name in &names[1..] {
+        len = first.bytes().zip(name.bytes()).take(len).take_while(|(a, b)| a == b).count();
+    }
+    // Back to a character boundary
+    while !first.is_char_boundary(len) {
+        len -= 1;
+    }
+    first[..len].to_string()
+}
@@ -0,0 +1,334 @@
+// `boole`: the library from the shell, one subcommand per exercise
+
+mod editor;
+mod repl;
+
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use boole::sat::{self, SatResult};
+use boole::kmap::KarnaughMap;
+use boole::synth::Spec;
+use boole::truth::TruthTable;
+use boole::{arith, cnf, curve, dimacs, nnf, sets};
+use boole::{Assignment, Formula};
+
+const USAGE: &str = "\
+usage: boole <command> [-f FILE]... [inputs]...
+
+Inputs are the arguments, then the lines of each FILE (`-` for stdin); with
+neither, the lines of stdin. Blank lines and lines starting with `#` are
+skipped. Formulas are RPN (`AB&C|`) or infix (`A & B | C`).
+
+commands:
+  eval [-s VAR=0|1]... FORMULA...   value of each formula, variables set with -s
+  table [--format F] [--subformulas] FORMULA...
+                                    truth table, F one of markdown (the
+                                    default), csv, json or latex; with
+                                    --subformulas a column per subformula
+  synth [--canonical] TABLE         a minimal formula with the truth table, given
+                                    as CSV or Markdown with `-` for don't-care;
+                                    with --canonical the sum of minterms
+  kmap [--svg] FORMULA...           Karnaugh map with the groups of a minimal
+                                    cover, up to 6 variables; --svg for an image
+  nnf FORMULA...                    negation normal form
+  cnf FORMULA...                    conjunctive normal form
+  sat [--dimacs] FORMULA...         a model, as SAT solvers print it; with
+                                    --dimacs the inputs are one DIMACS CNF file
+  classify FORMULA...               tautology, contradiction or contingent
+  powerset ELEMENT...               every subset of the set
+  set-eval FORMULA SET...           the formula over sets written `1,2,3`, with
+                                    A the first set, B the second...
+  zmap X Y                          Z-order curve value of the point
+  zunmap VALUE...                   point of each curve value
+  gray N...                         Gray code of each number
+  repl                              interactive prompt, :help lists its commands
+
+exit status: 0 on success, 1 on invalid input, 2 on bad usage; sat exits
+with 10 when every formula is satisfiable and 20 otherwise.";
+
+const SATISFIABLE: u8 = 10;
+const UNSATISFIABLE: u8 = 20;
+
+enum Error {
+    /// Wrong command or options: the usage is printed.
+    Usage(String),
+    /// Input that could not be read or parsed.
+    Input(String),
+}
+
+struct Options {
+    inputs: Vec<String>,
+    assignment: Assignment,
+    dimacs: bool,
+    format: Format,
+    subformulas: bool,
+    canonical: bool,
+    svg: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Markdown,
+    Csv,
+    Json,
+    Latex,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    if command == "-h" || command == "--help" {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    if command == "repl" {
+        return match repl::repl() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::from(1)
+            }
+        };
+    }
+
+    match options(command, args).and_then(|options| run(command, options)) {
+        Ok(code) => ExitCode::from(code),
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(Error::Input(message)) => {
+            eprintln!("{}", message);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn options(command: &str, args: &[String]) -> Result<Options, Error> {
+    let mut options = Options { inputs: Vec::new(), assignment: Assignment::new(), dimacs: false, format: Format::Markdown, subformulas: false, canonical: false, svg: false };
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| Error::Usage(format!("'{}' needs a value", flag)));
+        match arg.as_str() {
+            "-f" | "--file" => files.push(value(arg)?.clone()),
+            "-s" | "--set" => {
+                only(arg, command, "eval")?;
+                let binding = value(arg)?;
+                let (var, value) = match binding.split_once('=') {
+                    Some((var, "0")) => (var, false),
+                    Some((var, "1")) => (var, true),
+                    _ => return Err(Error::Usage(format!("expected VAR=0 or VAR=1, not '{}'", binding))),
+                };
+                let var = boole::parse(var)
+                    .ok()
+                    .and_then(|f| if let Formula::Var(var) = f { Some(var) } else { None })
+                    .ok_or_else(|| Error::Usage(format!("'{}' is not a variable name", var)))?;
+                options.assignment.insert(var, value);
+            }
+            "--dimacs" => {
+                only(arg, command, "sat")?;
+                options.dimacs = true;
+            }
+            "--format" => {
+                only(arg, command, "table")?;
+                options.format = match value(arg)?.as_str() {
+                    "markdown" | "md" => Format::Markdown,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    "latex" | "tex" => Format::Latex,
+                    format => return Err(Error::Usage(format!("unknown format '{}', expected markdown, csv, json or latex", format))),
+                };
+            }
+            "--subformulas" => {
+                only(arg, command, "table")?;
+                options.subformulas = true;
+            }
+            "--canonical" => {
+                only(arg, command, "synth")?;
+                options.canonical = true;
+            }
+            "--svg" => {
+                only(arg, command, "kmap")?;
+                options.svg = true;
+            }
+            // `-` alone is stdin and `-1` a negative number, not options
+            _ if arg.len() > 1 && arg.starts_with('-') && !arg[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                return Err(Error::Usage(format!("unknown option '{}'", arg)));
+            }
+            _ => options.inputs.push(arg.clone()),
+        }
+    }
+
+    if files.is_empty() && options.inputs.is_empty() {
+        files.push("-".to_string());
+    }
+    for file in files {
+        let text = if file == "-" {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text)
+        } else {
+            fs::read_to_string(&file)
+        };
+        let text = text.map_err(|e| Error::Input(format!("error: cannot read {}: {}", file, e)))?;
+        if options.dimacs {
+            // Kept whole, comments included, for the DIMACS parser
+            options.inputs.push(text);
+            continue;
+        }
+        let lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        options.inputs.extend(lines.map(String::from));
+    }
+    Ok(options)
+}
+
+// An option that only makes sense for one command.
+fn only(flag: &str, command: &str, owner: &str) -> Result<(), Error> {
+    if command != owner {
+        return Err(Error::Usage(format!("'{}' is an option of {}, not {}", flag, owner, command)));
+    }
+    Ok(())
+}
+
+fn run(command: &str, options: Options) -> Result<u8, Error> {
+    let inputs = &options.inputs;
+    match command {
+        "eval" => {
+            for f in formulas(inputs)? {
+                if let Some(var) = f.vars().into_iter().find(|var| !options.assignment.contains_key(var)) {
+                    return Err(Error::Input(format!("error: '{}' has no value, set it with -s {}=0 or -s {}=1", var, var, var)));
+                }
+                println!("{}", f.eval(&options.assignment) as u8);
+            }
+        }
+        "table" => {
+            for (i, f) in formulas(inputs)?.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                let table = if options.subformulas { TruthTable::with_subformulas(f) } else { TruthTable::new(f) };
+                let table = table.map_err(|e| Error::Input(format!("error: {}", e)))?;
+                print!(
+                    "{}",
+                    match options.format {
+                        Format::Markdown => table.to_markdown(),
+                        Format::Csv => table.to_csv(),
+                        Format::Json => table.to_json(),
+                        Format::Latex => table.to_latex(),
+                    }
+                );
+            }
+        }
+        "synth" => {
+            // Arguments and lines alike are the rows of one table
+            let spec = Spec::parse(&inputs.join("\n")).map_err(|e| Error::Input(format!("error: {}", e)))?;
+            println!("{}", if options.canonical { spec.sum_of_minterms() } else { spec.minimize() });
+        }
+        "kmap" => {
+            for (i, f) in formulas(inputs)?.iter().enumerate() {
+                let map = KarnaughMap::new(f).map_err(|e| Error::Input(format!("error: {}", e)))?.with_groups();
+                if i > 0 {
+                    println!();
+                }
+                print!("{}", if options.svg { map.to_svg() } else { map.to_ascii() });
+            }
+        }
+        "nnf" => formulas(inputs)?.iter().for_each(|f| println!("{}", nnf::to_nnf(f))),
+        "cnf" => formulas(inputs)?.iter().for_each(|f| println!("{}", cnf::to_cnf(f))),
+        "sat" if options.dimacs => {
+            let text = inputs.join("\n");
+            let cnf = dimacs::parse(&text).map_err(|e| Error::Input(format!("error: {}", e)))?;
+            let result = sat::solve_cnf(&cnf);
+            print!("{}", dimacs::write_result(&result));
+            return Ok(if result == SatResult::Unsat { UNSATISFIABLE } else { SATISFIABLE });
+        }
+        "sat" => {
+            let mut code = SATISFIABLE;
+            for f in formulas(inputs)? {
+                let result = sat::solve(&f);
+                if result == SatResult::Unsat {
+                    code = UNSATISFIABLE;
+                }
+                print!("{}", dimacs::write_result(&result));
+            }
+            return Ok(code);
+        }
+        "classify" => formulas(inputs)?.iter().for_each(|f| println!("{}", sat::classify(f))),
+        "powerset" => {
+            let set = numbers::<i32>(inputs, "an integer")?;
+            for subset in sets::powerset(&set) {
+                println!("{}", show_set(&subset));
+            }
+        }
+        "set-eval" => {
+            let Some((formula, set_inputs)) = inputs.split_first() else {
+                return Err(Error::Usage("set-eval needs a formula".to_string()));
+            };
+            let f = parse(formula)?;
+            let sets = set_inputs.iter().map(|input| parse_set(input)).collect::<Result<Vec<_>, _>>()?;
+            let result = sets::eval_set(&f, &sets).map_err(|e| Error::Input(format!("error: {}", e)))?;
+            println!("{}", show_set(&result));
+        }
+        "zmap" => {
+            let coordinates = numbers::<u16>(inputs, "a coordinate from 0 to 65535")?;
+            if coordinates.len() % 2 != 0 {
+                return Err(Error::Usage("zmap takes coordinates in pairs".to_string()));
+            }
+            for point in coordinates.chunks(2) {
+                println!("{}", curve::map(point[0], point[1]));
+            }
+        }
+        "zunmap" => {
+            for value in numbers::<f64>(inputs, "a number")? {
+                let (x, y) = curve::reverse_map(value)
+                    .ok_or_else(|| Error::Input(format!("error: {} is out of the range [0, 1]", value)))?;
+                println!("{} {}", x, y);
+            }
+        }
+        "gray" => {
+            for n in numbers::<u32>(inputs, "an integer from 0 to 4294967295")? {
+                println!("{}", arith::gray_code(n));
+            }
+        }
+        _ => return Err(Error::Usage(format!("unknown command '{}'", command))),
+    }
+    Ok(0)
+}
+
+fn parse(formula: &str) -> Result<Formula, Error> {
+    boole::parse(formula).map_err(|e| Error::Input(e.render(formula)))
+}
+
+fn formulas(inputs: &[String]) -> Result<Vec<Formula>, Error> {
+    if inputs.is_empty() {
+        return Err(Error::Usage("no formula given".to_string()));
+    }
+    inputs.iter().map(|input| parse(input)).collect()
+}
+
+// Every whitespace or comma separated number of the inputs.
+fn numbers<T: std::str::FromStr>(inputs: &[String], what: &str) -> Result<Vec<T>, Error> {
+    inputs
+        .iter()
+        .flat_map(|input| input.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().map_err(|_| Error::Input(format!("error: '{}' is not {}", token, what))))
+        .collect()
+}
+
+// A set written `1,2,3`, optionally in braces or brackets; `{}` is empty.
+fn parse_set(input: &str) -> Result<Vec<i32>, Error> {
+    let inner = input.trim().trim_start_matches(['{', '[']).trim_end_matches(['}', ']']);
+    numbers(&[inner.to_string()], "an integer")
+}
+
+fn show_set(set: &[i32]) -> String {
+    let elements: Vec<String> = set.iter().map(|x| x.to_string()).collect();
+    format!("{{{}}}", elements.join(", "))
+}
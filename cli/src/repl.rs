@@ -0,0 +1,256 @@
+// `boole repl`: named formulas, variable bindings and commands on a prompt
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::PathBuf;
+
+use boole::sat::{self, Equivalence, SatResult};
+use boole::truth::TruthTable;
+use boole::{cnf, nnf};
+use boole::{Assignment, Formula, Var};
+
+use crate::editor::Editor;
+
+const HELP: &str = "\
+  EXPR                 value of the formula, every variable being bound
+  let NAME = EXPR      names a formula; NAME then stands for it in later ones,
+                       and cannot be a variable already used
+  :set VAR=0|1...      binds variables, fixed in values, :table, :sat and :eq
+  :unset [VAR...]      unbinds the variables, all of them without arguments
+  :list                named formulas and bound variables
+  :table EXPR          truth table over the unbound variables
+  :nnf EXPR            negation normal form
+  :cnf EXPR            conjunctive normal form
+  :sat EXPR            a satisfying assignment, if any
+  :classify EXPR       tautology, contradiction or contingent
+  :eq F, G             whether F and G are equivalent, else where they differ
+  :help                this list
+  :quit                leaves, as does Ctrl-D
+Formulas are RPN (`AB&C|`) or infix (`A & B | C`). Tab completes variable
+and formula names, and up and down walk through the history.";
+
+const COMMANDS: [&str; 11] = [":set", ":unset", ":list", ":table", ":nnf", ":cnf", ":sat", ":classify", ":eq", ":help", ":quit"];
+
+/// What the prompt remembers between lines.
+#[derive(Default)]
+pub struct Session {
+    // Stored with earlier names already replaced
+    definitions: BTreeMap<Var, Formula>,
+    bindings: Assignment,
+    // Every variable met so far, for completion
+    seen: BTreeSet<Var>,
+}
+
+impl Session {
+    /// Runs one line, returning what to print. `Ok(None)` asks to quit.
+    /// Errors are ready to print, `error: ` included.
+    pub fn run(&mut self, line: &str) -> Result<Option<String>, String> {
+        let line = line.trim();
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+
+        let output = match command {
+            "" => String::new(),
+            ":q" | ":quit" => return Ok(None),
+            ":h" | ":help" => HELP.to_string(),
+            "let" => self.define(rest)?,
+            ":set" => self.set(rest)?,
+            ":unset" => self.unset(rest)?,
+            ":list" => self.list(),
+            ":table" => TruthTable::new(&self.bound_formula(rest)?).map_err(|e| format!("error: {}", e))?.to_markdown().trim_end().to_string(),
+            ":nnf" => nnf::to_nnf(&self.formula(rest)?).to_string(),
+            ":cnf" => cnf::to_cnf(&self.formula(rest)?).to_string(),
+            ":sat" => match sat::solve(&self.bound_formula(rest)?) {
+                SatResult::Sat(model) => format!("satisfiable: {}", show(&model)),
+                SatResult::Unsat => "unsatisfiable".to_string(),
+            },
+            ":classify" => sat::classify(&self.bound_formula(rest)?).to_string(),
+            ":eq" => self.equivalent(rest)?,
+            _ if command.starts_with(':') => return Err(format!("error: unknown command '{}', see :help", command)),
+            _ => self.value(line)?,
+        };
+        Ok(Some(output))
+    }
+
+    /// Names starting with `prefix`: commands for `:`, else variables and
+    /// named formulas.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let names: Vec<String> = if prefix.starts_with(':') {
+            COMMANDS.iter().map(|command| command.to_string()).collect()
+        } else {
+            self.seen.iter().chain(self.definitions.keys()).map(|var| var.name().to_string()).collect()
+        };
+        let names: BTreeSet<String> = names.into_iter().filter(|name| name.starts_with(prefix)).collect();
+        names.into_iter().collect()
+    }
+
+    // The formula with named formulas expanded.
+    fn formula(&mut self, text: &str) -> Result<Formula, String> {
+        if text.is_empty() {
+            return Err("error: expected a formula".to_string());
+        }
+        let f = boole::parse(text).map_err(|e| e.render(text))?;
+        let f = f.substitute(&|var| self.definitions.get(&var).cloned());
+        self.seen.extend(f.vars());
+        Ok(f)
+    }
+
+    // The formula with bound variables replaced by their value.
+    fn bound(&self, f: &Formula) -> Formula {
+        f.substitute(&|var| self.bindings.get(&var).map(|&value| Formula::Const(value)))
+    }
+
+    fn bound_formula(&mut self, text: &str) -> Result<Formula, String> {
+        let f = self.formula(text)?;
+        Ok(self.bound(&f))
+    }
+
+    fn value(&mut self, text: &str) -> Result<String, String> {
+        let f = self.formula(text)?;
+        let unbound: Vec<String> = f.vars().into_iter().filter(|var| !self.bindings.contains_key(var)).map(|var| var.to_string()).collect();
+        if !unbound.is_empty() {
+            return Err(format!("error: unbound: {}; bind with :set or try :table", unbound.join(", ")));
+        }
+        Ok((f.eval(&self.bindings) as u8).to_string())
+    }
+
+    fn define(&mut self, rest: &str) -> Result<String, String> {
+        let (name, text) = rest.split_once('=').ok_or("error: expected let NAME = EXPR")?;
+        let name = variable(name.trim())?;
+        // A name would hide the variable from :set, which binds variables only
+        if self.seen.contains(&name) {
+            return Err(format!("error: '{}' is already a variable", name));
+        }
+        let f = self.formula(text.trim())?;
+        if f.vars().contains(&name) {
+            return Err(format!("error: '{}' cannot be defined in terms of itself", name));
+        }
+        let output = format!("{} = {}", name, f);
+        self.definitions.insert(name, f);
+        Ok(output)
+    }
+
+    fn set(&mut self, rest: &str) -> Result<String, String> {
+        let mut bindings = Vec::new();
+        for binding in rest.split_whitespace() {
+            let (var, value) = match binding.split_once('=') {
+                Some((var, "0")) => (var, false),
+                Some((var, "1")) => (var, true),
+                _ => return Err(format!("error: expected VAR=0 or VAR=1, not '{}'", binding)),
+            };
+            let var = variable(var)?;
+            if self.definitions.contains_key(&var) {
+                return Err(format!("error: '{}' names a formula, not a variable", var));
+            }
+            bindings.push((var, value));
+        }
+        if bindings.is_empty() {
+            return Err("error: expected VAR=0 or VAR=1".to_string());
+        }
+        self.seen.extend(bindings.iter().map(|&(var, _)| var));
+        self.bindings.extend(bindings);
+        Ok(String::new())
+    }
+
+    fn unset(&mut self, rest: &str) -> Result<String, String> {
+        if rest.is_empty() {
+            self.bindings.clear();
+        }
+        for name in rest.split_whitespace() {
+            self.bindings.remove(&variable(name)?);
+        }
+        Ok(String::new())
+    }
+
+    fn list(&self) -> String {
+        let mut lines: Vec<String> = self.definitions.iter().map(|(name, f)| format!("{} = {}", name, f)).collect();
+        if !self.bindings.is_empty() {
+            lines.push(format!("bound: {}", show(&self.bindings)));
+        }
+        lines.join("\n")
+    }
+
+    fn equivalent(&mut self, rest: &str) -> Result<String, String> {
+        let (f, g) = match rest.split_once(',') {
+            Some(pair) => pair,
+            None => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+                [f, g] => (f, g),
+                _ => return Err("error: expected :eq F, G".to_string()),
+            },
+        };
+        let (f, g) = (self.formula(f.trim())?, self.formula(g.trim())?);
+        let (f, g) = (self.bound(&f), self.bound(&g));
+        Ok(match sat::equivalent(&f, &g) {
+            Equivalence::Equivalent => "equivalent".to_string(),
+            Equivalence::Counterexample(row) => {
+                format!("differ at {}: {} against {}", show(&row), f.eval(&row) as u8, g.eval(&row) as u8)
+            }
+        })
+    }
+}
+
+fn variable(name: &str) -> Result<Var, String> {
+    match boole::parse(name) {
+        Ok(Formula::Var(var)) => Ok(var),
+        _ => Err(format!("error: '{}' is not a variable name", name)),
+    }
+}
+
+fn show(assignment: &Assignment) -> String {
+    let values: Vec<String> = assignment.iter().map(|(var, &value)| format!("{}={}", var, value as u8)).collect();
+    values.join(", ")
+}
+
+fn history_file() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".boole_history"))
+}
+
+/// Reads lines until `:quit` or end of input. On a terminal, lines are
+/// edited in place and kept in `~/.boole_history`; otherwise they are read
+/// as they come, without prompt, so a script can be piped in.
+pub fn repl() -> io::Result<()> {
+    let mut session = Session::default();
+    if !io::stdin().is_terminal() {
+        for line in io::stdin().lock().lines() {
+            if !report(&mut session, &line?) {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    let history = history_file();
+    let mut editor = Editor::new(&history.as_ref().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default());
+    println!("boole: formulas in RPN or infix, :help for commands");
+    while let Some(line) = editor.read_line("> ", &|prefix| session.completions(prefix))? {
+        editor.add_history(&line);
+        if !report(&mut session, &line) {
+            break;
+        }
+    }
+    if let Some(path) = history {
+        // History is a convenience: losing it is not worth an error
+        let _ = fs::write(path, editor.history());
+    }
+    Ok(())
+}
+
+// Runs a line and prints its output or error, false once asked to quit.
+fn report(session: &mut Session, line: &str) -> bool {
+    match session.run(line) {
+        Ok(Some(output)) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+            true
+        }
+        Ok(None) => false,
+        Err(message) => {
+            eprintln!("{}", message);
+            true
+        }
+    }
+}
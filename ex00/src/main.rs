@@ -2,16 +2,7 @@
 // - To start a new project use "cargo new <project_name>"
 // - To run the project use "cargo run"
 
-fn adder(a: u32, b: u32) -> u32 {
-    let mut x = a;
-    let mut y = b;
-    while y != 0 {
-        let carry = (x & y) << 1;
-        x = x ^ y;
-        y = carry;
-    }
-    x
-}
+use boole::arith::adder;
 
 fn main() {
     let tests = [
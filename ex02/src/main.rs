@@ -1,6 +1,4 @@
-fn gray_code(n: u32) -> u32 {
-    n ^ (n >> 1) // XOR with right-shifted n
-}
+use boole::arith::gray_code;
 
 fn main() {
     let tests = [
@@ -1,85 +1,24 @@
-// Evaluate a Boolean expression in Reverse Polish Notation (RPN)
+use boole::ParseError;
+
+// Evaluate a Boolean expression in Reverse Polish Notation (RPN) or infix
 // with 0, 1 and operators &, |, >, =, !, ^
 fn eval_formula(formula: &str) -> bool {
-    let mut stack: Vec<bool> = Vec::new();
-    
-    for ch in formula.chars() {
-        match ch {
-            '0' => stack.push(false),
-            '1' => stack.push(true),
-            '!' => {
-                if stack.is_empty() {
-                    eprintln!("Error: Not enough operands for ! in '{}'", formula);
-                    return false;
-                }
-                let a = stack.pop().unwrap();
-                stack.push(!a);
-            }
-            '&' => {
-                if stack.len() < 2 {
-                    eprintln!("Error: Not enough operands for & in '{}'", formula);
-                    return false;
-                }
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                stack.push(a && b);
-            }
-            '|' => {
-                if stack.len() < 2 {
-                    eprintln!("Error: Not enough operands for | in '{}'", formula);
-                    return false;
-                }
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                stack.push(a || b);
-            }
-            '^' => {
-                if stack.len() < 2 {
-                    eprintln!("Error: Not enough operands for ^ in '{}'", formula);
-                    return false;
-                }
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                // XOR: true when operands are different
-                stack.push(a != b);
-            }
-            '>' => {
-                if stack.len() < 2 {
-                    eprintln!("Error: Not enough operands for > in '{}'", formula);
-                    return false;
-                }
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                // Material implication: A > B is equivalent to !A | B
-                stack.push(!a || b);
-            }
-            '=' => {
-                if stack.len() < 2 {
-                    eprintln!("Error: Not enough operands for = in '{}'", formula);
-                    return false;
-                }
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                // Equivalence: A = B is true when both have the same value
-                stack.push(a == b);
-            }
-            ' ' => {
-                // Skip whitespace
-                continue;
-            }
-            _ => {
-                eprintln!("Error: Invalid character '{}' in '{}'", ch, formula);
-                return false;
-            }
+    match parse_constant(formula) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e.render(formula));
+            false
         }
     }
-    
-    if stack.len() != 1 {
-        eprintln!("Error: Invalid formula '{}' - stack has {} elements", formula, stack.len());
-        return false;
+}
+
+// Variables are not part of this exercise, so they are unknown symbols here
+fn parse_constant(formula: &str) -> Result<bool, ParseError> {
+    let is_name = |c: char| c.is_ascii_alphabetic() || c == '_' || c == '"';
+    if let Some((offset, symbol)) = formula.char_indices().find(|&(_, c)| is_name(c)) {
+        return Err(ParseError::UnknownSymbol { symbol, offset });
     }
-    
-    stack[0]
+    boole::parse(formula).map(|f| f.eval(&Default::default()))
 }
 
 fn main() {
@@ -94,6 +33,10 @@ fn main() {
         ("10^", true),     // 1 XOR 0 = true (different values)
         ("11^", false),    // 1 XOR 1 = false (same values)
         ("00^", false),    // 0 XOR 0 = false (same values)
+        ("1 & 0", false),        // infix: 1 AND 0 = false
+        ("!(1 -> 0)", true),     // infix: NOT (1 > 0) = NOT false = true
+        ("1 ^ 1 <-> 0", true),   // infix: (1 XOR 1) = 0 = true
+        ("0 -> 0 -> 0", true),   // infix: 0 > (0 > 0) = true
     ];
 
     for (formula, expected) in tests {
@@ -102,5 +45,22 @@ fn main() {
         assert_eq!(result, expected, "Test failed for {}", formula);
     }
     
+    let errors = [
+        ("", ParseError::EmptyInput),
+        ("1x|", ParseError::UnknownSymbol { symbol: 'x', offset: 1 }),
+        ("1A|", ParseError::UnknownSymbol { symbol: 'A', offset: 1 }),
+        ("0!&", ParseError::OperandUnderflow { op: '&', offset: 2, depth: 1 }),
+        ("10&1 0", ParseError::LeftoverOperands { offset: 3, depth: 3 }),
+        ("(1 & 0", ParseError::UnclosedParen { offset: 0 }),
+        ("1 & | 0", ParseError::ExpectedOperand { offset: 4 }),
+        ("(1 & 0) 1", ParseError::ExpectedOperator { offset: 8 }),
+    ];
+
+    for (formula, expected) in errors {
+        let result = parse_constant(formula);
+        println!("{}", expected.render(formula));
+        assert_eq!(result, Err(expected), "Test failed for {}", formula);
+    }
+
     println!("All tests passed!");
 }
\ No newline at end of file
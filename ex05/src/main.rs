@@ -1,93 +1,92 @@
+use boole::nnf;
+use boole::sat::{self, Equivalence};
+use boole::{Assignment, Formula};
+
 fn negation_normal_form(formula: &str) -> String {
-    let mut stack: Vec<(String, String, char)> = Vec::new();
-    for ch in formula.chars() {
-        match ch {
-            'A' | 'B' | 'C' => stack.push((ch.to_string(), "".to_string(), '\0')),
-            '&' | '|' => {
-                if stack.len() < 2 { return err(formula, "Insufficient operands"); }
-                let (v2, v2_op2, v2_op) = stack.pop().unwrap();
-                let (v1, v1_op2, v1_op) = stack.pop().unwrap();
-                
-                // Reconstruct the full expressions
-                let expr1 = if v1_op == '\0' { v1 } else { format!("{}{}{}", v1, v1_op2, v1_op) };
-                let expr2 = if v2_op == '\0' { v2 } else { format!("{}{}{}", v2, v2_op2, v2_op) };
-                
-                stack.push((expr1, expr2, ch));
-            }
-            '^' => {
-                if stack.len() < 2 { return err(formula, "Insufficient operands"); }
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}!{}&{}!{}!&|", v1, v2, v1, v2), "".to_string(), '\0'));
-            }
-            '!' => {
-                if stack.is_empty() { return err(formula, "Insufficient operands for '!'"); }
-                let (v1, v2, op) = stack.pop().unwrap();
-                if op == '\0' { 
-                    stack.push((v1 + "!", "".to_string(), '\0')); 
-                }
-                else if op == '&' { 
-                    // De Morgan's law: !(A & B) = !A | !B
-                    let neg_v1 = negate(&v1);
-                    let neg_v2 = negate(&v2);
-                    stack.push((format!("{}{}|", neg_v1, neg_v2), "".to_string(), '\0')); 
-                }
-                else if op == '|' { 
-                    // De Morgan's law: !(A | B) = !A & !B
-                    let neg_v1 = negate(&v1);
-                    let neg_v2 = negate(&v2);
-                    stack.push((format!("{}{}&", neg_v1, neg_v2), "".to_string(), '\0')); 
-                }
-                else { return err(formula, "Invalid expression for '!'"); }
-            }
-            '>' => {
-                if stack.len() < 2 { return err(formula, "Insufficient operands"); }
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}!{}|", v1, v2), "".to_string(), '\0'));
-            }
-            '=' => {
-                if stack.len() < 2 { return err(formula, "Insufficient operands"); }
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}{}&{}!{}!&|", v1, v2, v1, v2), "".to_string(), '\0'));
-            }
-            _ => return err(formula, &format!("Invalid character '{}'", ch)),
+    match boole::parse(formula) {
+        Ok(f) => nnf::to_nnf(&f).to_string(),
+        Err(e) => {
+            eprintln!("{}", e.render(formula));
+            String::new()
         }
     }
-    if stack.len() != 1 { 
-        err(formula, "Invalid formula") 
-    } else { 
-        let (v1, v2, op) = stack.pop().unwrap();
-        if op == '\0' { v1 } else { format!("{}{}{}", v1, v2, op) }
-    }
 }
 
-fn negate(expr: &str) -> String {
-    if expr.len() == 1 {
-        return format!("{}!", expr);
+fn main() {
+    let tests = [
+        ("AB&!", "A!B!|"),
+        ("AB|!", "A!B!&"),
+        ("AB>", "A!B|"),
+        ("AB=", "AB&A!B!&|"),
+        ("AB|C&!", "A!B!&C!|"),
+        // Any depth, any variable
+        ("AB&C|!", "A!B!|C!&"),
+        ("AB^!", "AB&A!B!&|"),
+        ("AB>!", "AB!&"),
+        ("PQ=R>!", "PQ&P!Q!&|R!&"),
+        ("A!!!!!", "A!"),
+        ("X1!&!", "X!1|"),
+        ("req_valid grant[3]!&!", "req_valid!grant[3]|"),
+    ];
+
+    for (formula, expected) in tests {
+        let result = negation_normal_form(formula);
+        println!("{} -> {} (expected: {})", formula, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", formula);
     }
-    
-    // For simple expressions like "AB&" or "AB|"
-    if expr.len() == 3 && expr.chars().nth(2).map_or(false, |c| c == '&' || c == '|') {
-        let op = expr.chars().nth(2).unwrap();
-        let a = &expr[0..1];
-        let b = &expr[1..2];
-        match op {
-            '&' => format!("{}!{}!|", a, b),  // !(A & B) = !A | !B
-            '|' => format!("{}!{}!&", a, b),  // !(A | B) = !A & !B
-            _ => format!("{}!", expr)
+
+    // Random formulas over four variables: the result is in NNF and agrees
+    // with the input on the whole truth table
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut random = move |n: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % n
+    };
+
+    for _ in 0..1000 {
+        let f = random_formula(&mut random, 6);
+        let result = nnf::to_nnf(&f);
+        assert!(nnf::is_nnf(&result), "Not in NNF for {}: {}", f, result);
+        for row in 0..16u32 {
+            let assignment: Assignment = "ABCD".chars().enumerate().map(|(i, v)| (v.into(), row >> i & 1 == 1)).collect();
+            assert_eq!(f.eval(&assignment), result.eval(&assignment), "Not equivalent for {}: {}", f, result);
         }
-    } else {
-        format!("{}!", expr)
     }
-}
+    println!("1000 random formulas: in NNF and equivalent");
 
-fn err(formula: &str, msg: &str) -> String {
-    eprintln!("Error: {} in '{}'", msg, formula);
-    String::new()
+    // Checked by the solver instead of a truth table: 40 variables, and a
+    // wrong NNF caught with an assignment telling them apart
+    let wide = (1..20).fold(String::from("x0 y0&!"), |acc, i| format!("{} x{} y{}>!|", acc, i, i));
+    let f = boole::parse(&wide).unwrap();
+    assert_eq!(sat::equivalent(&f, &nnf::to_nnf(&f)), Equivalence::Equivalent, "Not equivalent for 40 variables");
+    println!("equivalent(<40 variables>, to_nnf) = Equivalent");
+    let (f, wrong) = (boole::parse("AB&!").unwrap(), boole::parse("A!B!&").unwrap());
+    let Equivalence::Counterexample(row) = sat::equivalent(&f, &wrong) else { panic!("AB&! and A!B!& are not equivalent") };
+    println!("equivalent(AB&!, A!B!&) = Counterexample({:?})", row);
+    assert_ne!(f.eval(&row), wrong.eval(&row), "Counterexample does not distinguish them");
+
+    for (formula, expected) in [("A!B|", true), ("A!!", false), ("AB&!", false), ("AB^", false), ("1A!&", true)] {
+        let result = nnf::is_nnf(&boole::parse(formula).unwrap());
+        println!("is_nnf({}) = {}, expected: {}", formula, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", formula);
+    }
 }
 
-fn main() {
-    ["AB&!", "AB|!", "AB>", "AB=", "AB|C&!"].iter().for_each(|&f| println!("{}", negation_normal_form(f)));
-}
\ No newline at end of file
+fn random_formula(random: &mut impl FnMut(u64) -> u64, depth: u32) -> Formula {
+    if depth == 0 || random(4) == 0 {
+        return match random(10) {
+            0 => Formula::Const(random(2) == 1),
+            n => Formula::var(char::from(b'A' + (n % 4) as u8)),
+        };
+    }
+    match random(6) {
+        0 => !random_formula(random, depth - 1),
+        op => {
+            let a = random_formula(random, depth - 1);
+            let b = random_formula(random, depth - 1);
+            Formula::binary(['&', '|', '^', '>', '='][op as usize - 1], a, b).unwrap()
+        }
+    }
+}
@@ -1,121 +1,26 @@
-fn conjunctive_normal_form(formula: &str) -> String {
-    to_cnf(&to_nnf(formula))
-}
-
-fn to_nnf(formula: &str) -> String {
-    let mut stack: Vec<(String, String, char)> = Vec::new();
-    
-    for ch in formula.chars() {
-        match ch {
-            'A'..='Z' => stack.push((ch.to_string(), String::new(), '\0')),
-            '&' | '|' => {
-                if stack.len() >= 2 {
-                    let (v2, v2_op2, v2_op) = stack.pop().unwrap();
-                    let (v1, v1_op2, v1_op) = stack.pop().unwrap();
-                    
-                    let expr1 = if v1_op == '\0' { v1 } else { format!("{}{}{}", v1, v1_op2, v1_op) };
-                    let expr2 = if v2_op == '\0' { v2 } else { format!("{}{}{}", v2, v2_op2, v2_op) };
-                    
-                    stack.push((expr1, expr2, ch));
-                }
-            }
-            '!' => {
-                if let Some((v1, v2, op)) = stack.pop() {
-                    match op {
-                        '\0' => stack.push((v1 + "!", String::new(), '\0')),
-                        '&' => stack.push((format!("{}{}|", negate(&v1), negate(&v2)), String::new(), '\0')),
-                        '|' => stack.push((format!("{}{}&", negate(&v1), negate(&v2)), String::new(), '\0')),
-                        _ => {}
-                    }
-                }
-            }
-            '>' => if stack.len() >= 2 {
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}!{}|", v1, v2), String::new(), '\0'));
-            }
-            '=' => if stack.len() >= 2 {
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}{}&{}!{}!&|", v1, v2, v1, v2), String::new(), '\0'));
-            }
-            '^' => if stack.len() >= 2 {
-                let (v2, _, _) = stack.pop().unwrap();
-                let (v1, _, _) = stack.pop().unwrap();
-                stack.push((format!("{}!{}&{}!{}!&|", v1, v2, v1, v2), String::new(), '\0'));
-            }
-            _ => {}
-        }
-    }
-    
-    if let Some((v1, v2, op)) = stack.pop() {
-        if op == '\0' { v1 } else { format!("{}{}{}", v1, v2, op) }
-    } else { String::new() }
-}
-
-fn to_cnf(formula: &str) -> String {
-    let mut stack: Vec<String> = Vec::new();
-    
-    for ch in formula.chars() {
-        match ch {
-            'A'..='Z' => stack.push(ch.to_string()),
-            '!' => if let Some(var) = stack.pop() { stack.push(format!("{}!", var)); }
-            '&' => if stack.len() >= 2 {
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                stack.push(if !a.contains('|') && !b.contains('|') {
-                    join_with(&[get_literals(&a), get_literals(&b)].concat(), '&')
-                } else { format!("{}{}&", a, b) });
-            }
-            '|' => if stack.len() >= 2 {
-                let b = stack.pop().unwrap();
-                let a = stack.pop().unwrap();
-                stack.push(if !a.contains('&') && !b.contains('&') {
-                    join_with(&[get_literals(&a), get_literals(&b)].concat(), '|')
-                } else { format!("{}{}|", a, b) });
-            }
-            _ => {}
-        }
-    }
-    
-    stack.pop().unwrap_or_default()
-}
+use boole::cnf::{self, Cnf, Encoding};
+use boole::models;
+use boole::sat::{self, Equivalence, SatResult};
+use boole::dnf;
 
-fn negate(expr: &str) -> String {
-    if expr.len() <= 2 {
-        format!("{}!", expr.chars().next().unwrap())
-    } else {
-        let (a, b, op) = (&expr[0..1], &expr[1..2], expr.chars().last().unwrap());
-        match op {
-            '&' => format!("{}!{}!|", a, b),
-            '|' => format!("{}!{}!&", a, b),
-            _ => format!("{}!", expr.chars().next().unwrap()),
+fn conjunctive_normal_form(formula: &str) -> String {
+    match boole::parse(formula) {
+        Ok(f) => cnf::to_cnf(&f).to_string(),
+        Err(e) => {
+            eprintln!("{}", e.render(formula));
+            String::new()
         }
     }
 }
 
-fn get_literals(expr: &str) -> Vec<String> {
-    let mut literals = Vec::new();
-    let mut chars = expr.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        if ch.is_alphabetic() {
-            let mut literal = ch.to_string();
-            if chars.peek() == Some(&'!') {
-                literal.push(chars.next().unwrap());
-            }
-            literals.push(literal);
+fn disjunctive_normal_form(formula: &str) -> String {
+    match boole::parse(formula) {
+        Ok(f) => dnf::to_dnf(&f).to_string(),
+        Err(e) => {
+            eprintln!("{}", e.render(formula));
+            String::new()
         }
     }
-    literals
-}
-
-fn join_with(items: &[String], op: char) -> String {
-    if items.len() == 1 {
-        items[0].clone()
-    } else {
-        items.join("") + &op.to_string().repeat(items.len() - 1)
-    }
 }
 
 fn main() {
@@ -127,8 +32,122 @@ fn main() {
         ("AB&C&D&", "ABCD&&&"),
         ("AB&!C!|", "A!B!C!||"),
         ("AB|!C!&", "A!B!C!&&"),
+        // `|` over `&` at any depth
+        ("AB&C|", "AC|BC|&"),
+        ("AB&CD&|", "AC|AD|BC|BD|&&&"),
+        ("AB&C|D&!", "A!B!D!||C!D!|&"),
+        ("AB=", "AB!|BA!|&"),
+        // Repeated literals, tautologies and subsumed clauses
+        ("AA|B&", "AB&"),
+        ("AA!|B&", "B"),
+        ("AB|A&", "A"),
+        ("AB&A|", "A"),
+        ("AA!&B|", "AB|A!B|&"),
+        ("AA!|", "1"),
     ] {
         let result = conjunctive_normal_form(input);
         println!("{} -> {} (expected: {})", input, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", input);
+    }
+
+    // Equivalent to the input, and in CNF, for every formula over up to 4 variables
+    // The last two nest `^` and `=` deep enough that distributing without
+    // simplifying at every step runs out of memory
+    for input in [
+        "AB&C|D&!",
+        "AB^CD=>",
+        "AB>C>!D^",
+        "ABCD&|&",
+        "AB=CD=|!",
+        "AB^C^D^",
+        "A!B!&C!D|=",
+        "AA>A&!AA^BA^=BA^B!>==",
+        "BD!!=BCD^=DC^C^^&0BC&DD>=|ADA>>1C&C>=^=",
+    ] {
+        let f = boole::parse(input).unwrap();
+        let result = cnf::to_cnf(&f);
+        assert!(cnf::is_cnf(&result), "Not in CNF for {}: {}", input, result);
+        for row in 0..16u32 {
+            let assignment = "ABCD".chars().enumerate().map(|(i, v)| (v.into(), row >> i & 1 == 1)).collect();
+            assert_eq!(f.eval(&assignment), result.eval(&assignment), "Not equivalent for {}: {}", input, result);
+        }
+        println!("{} -> {} (is_cnf, equivalent)", input, result);
     }
+
+    // The same with the solver, and a CNF missing a clause
+    for input in ["AB&C|D&!", "AB^CD=>", "AB>C>!D^", "AB=CD=|!"] {
+        let f = boole::parse(input).unwrap();
+        assert_eq!(sat::equivalent(&f, &cnf::to_cnf(&f)), Equivalence::Equivalent, "Not equivalent for {}", input);
+    }
+    let (f, wrong) = (boole::parse("AB=").unwrap(), boole::parse("AB!|").unwrap());
+    let Equivalence::Counterexample(row) = sat::equivalent(&f, &wrong) else { panic!("AB= and AB!| are not equivalent") };
+    println!("equivalent(AB=, AB!|) = Counterexample({:?})", row);
+    assert_eq!(row, [('A'.into(), true), ('B'.into(), false)].into(), "Test failed for AB=");
+
+    for (input, expected) in [("AB|C&", true), ("A!B|", true), ("0", true), ("AB&C|", false), ("AB|!", false), ("A!!", false), ("AB>", false)] {
+        let result = cnf::is_cnf(&boole::parse(input).unwrap());
+        println!("is_cnf({}) = {}, expected: {}", input, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", input);
+    }
+
+    // Disjunctive normal form, the dual: `&` distributed over `|`
+    for (input, expected) in [
+        ("AB|C&", "AC&BC&|"),
+        ("AB&C|", "AB&C|"),
+        ("AB|CD|&", "AC&AD&BC&BD&|||"),
+        ("AB^", "AB!&A!B&|"),
+        ("AB|!", "A!B!&"),
+        ("AB|A&", "A"),
+        ("AA!&B|", "B"),
+        ("AA!&", "0"),
+    ] {
+        let result = disjunctive_normal_form(input);
+        println!("dnf({}) = {} (expected: {})", input, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", input);
+        let f = boole::parse(input).unwrap();
+        let result = dnf::to_dnf(&f);
+        assert!(dnf::is_dnf(&result), "Not in DNF for {}", input);
+        let vars: Vec<_> = f.vars().into_iter().collect();
+        for row in 0..1 << vars.len() {
+            let assignment = boole::formula::row(&vars, row);
+            assert_eq!(f.eval(&assignment), result.eval(&assignment), "Not equivalent for {}", input);
+        }
+    }
+
+    // The formulas whose CNF needed simplifying at every step need it for
+    // the DNF as well
+    for input in ["AA>A&!AA^BA^=BA^B!>==", "BD!!=BCD^=DC^C^^&0BC&DD>=|ADA>>1C&C>=^="] {
+        let f = boole::parse(input).unwrap();
+        let result = dnf::to_dnf(&f);
+        println!("dnf({}) = {}", input, result);
+        assert!(dnf::is_dnf(&result), "Not in DNF for {}", input);
+        assert_eq!(sat::equivalent(&f, &result), Equivalence::Equivalent, "Not equivalent for {}", input);
+    }
+
+    // Tseitin and Plaisted–Greenbaum: linear size where distribution doubles
+    // the clauses with every term of AB&CD&|EF&|...
+    for terms in [2, 4, 8, 12] {
+        let f = (1..terms).fold(String::from("x0 y0&"), |acc, i| format!("{} x{} y{}&|", acc, i, i));
+        let f = boole::parse(&f).unwrap();
+        let sizes = [Encoding::Equivalent, Encoding::Tseitin, Encoding::PlaistedGreenbaum]
+            .map(|encoding| Cnf::encode(&f, encoding).clauses.len());
+        println!("{} terms: {:?} clauses (distribution, Tseitin, Plaisted-Greenbaum)", terms, sizes);
+        assert_eq!(sizes, [1 << terms, 6 * terms - 2, 3 * terms], "Test failed for {} terms", terms);
+    }
+
+    // Equisatisfiable: both encodings are satisfiable exactly when the formula is
+    for (input, expected) in [("AB&CD&|", true), ("AB^AB=&", false), ("A0|A!1&&", false), ("AB>A&B!&", false), ("AB=C^", true)] {
+        let f = boole::parse(input).unwrap();
+        for encoding in [Encoding::Tseitin, Encoding::PlaistedGreenbaum] {
+            let result = matches!(sat::solve_cnf(&Cnf::encode(&f, encoding)), SatResult::Sat(_));
+            println!("sat({:?} of {}) = {}, expected: {}", encoding, input, result, expected);
+            assert_eq!(result, expected, "Test failed for {:?} of {}", encoding, input);
+        }
+    }
+
+    // Tseitin keeps the number of models: 2^12 - 3^6 for 6 terms over 12 variables
+    let f = (1..6).fold(String::from("x0 y0&"), |acc, i| format!("{} x{} y{}&|", acc, i, i));
+    let count = models::count_models(&boole::parse(&f).unwrap());
+    println!("count_models(6 terms) = {}, expected: {}", count, 4096 - 729);
+    assert_eq!(count, 3367u64.into(), "Test failed for count_models");
 }
@@ -1,121 +1,109 @@
-use std::collections::{HashMap, HashSet};
+use boole::sat::{self, Classification, SatResult};
+use boole::solver::Solver;
 
 fn sat(formula: &str) -> bool {
-    // Extract all unique variables from the formula
-    let variables: HashSet<char> = formula
-        .chars()
-        .filter(|&c| c.is_alphabetic())
-        .collect();
-    
-    let var_list: Vec<char> = variables.into_iter().collect();
-    let num_vars = var_list.len();
-    
-    // If no variables, evaluate the formula directly
-    if num_vars == 0 {
-        return eval_formula(formula, &HashMap::new());
-    }
-    
-    // Try all possible combinations of truth values (2^n combinations)
-    for i in 0..(1 << num_vars) {
-        let mut assignment = HashMap::new();
-        
-        // Generate truth assignment for this iteration
-        for (j, &var) in var_list.iter().enumerate() {
-            assignment.insert(var, (i >> j) & 1 == 1);
-        }
-        
-        // Evaluate formula with this assignment
-        if eval_formula(formula, &assignment) {
-            return true;
+    match boole::parse(formula) {
+        Ok(f) => sat::sat(&f),
+        Err(e) => {
+            eprintln!("{}", e.render(formula));
+            false
         }
     }
-    
-    false
 }
 
-fn eval_formula(formula: &str, assignment: &HashMap<char, bool>) -> bool {
-    let mut stack: Vec<bool> = Vec::new();
-
-    for ch in formula.chars() {
-        match ch {
-            'A'..='Z' => {
-                // Get the value from assignment, default to false if not found
-                let value = assignment.get(&ch).copied().unwrap_or(false);
-                stack.push(value);
-            }
-            '!' => {
-                if let Some(val) = stack.pop() {
-                    stack.push(!val);
-                } else {
-                    return false; // Invalid formula
-                }
-            }
-            '&' => {
-                if stack.len() >= 2 {
-                    let b = stack.pop().unwrap();
-                    let a = stack.pop().unwrap();
-                    stack.push(a && b);
-                } else {
-                    return false; // Invalid formula
-                }
-            }
-            '|' => {
-                if stack.len() >= 2 {
-                    let b = stack.pop().unwrap();
-                    let a = stack.pop().unwrap();
-                    stack.push(a || b);
-                } else {
-                    return false; // Invalid formula
-                }
-            }
-            '^' => {
-                if stack.len() >= 2 {
-                    let b = stack.pop().unwrap();
-                    let a = stack.pop().unwrap();
-                    stack.push(a ^ b); // XOR
-                } else {
-                    return false; // Invalid formula
-                }
-            }
-            '>' => {
-                if stack.len() >= 2 {
-                    let b = stack.pop().unwrap();
-                    let a = stack.pop().unwrap();
-                    stack.push(!a || b); // Implication: A -> B = !A | B
-                } else {
-                    return false; // Invalid formula
-                }
-            }
-            '=' => {
-                if stack.len() >= 2 {
-                    let b = stack.pop().unwrap();
-                    let a = stack.pop().unwrap();
-                    stack.push(a == b); // Equivalence
-                } else {
-                    return false; // Invalid formula
-                }
+fn main() {
+    // `ex07 <formula>...` classifies each formula instead of running the tests
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        for formula in &args {
+            match boole::parse(formula) {
+                Ok(f) => println!("{}: {}", formula, sat::classify(&f)),
+                Err(e) => eprintln!("{}", e.render(formula)),
             }
-            _ => {
-                // Ignore whitespace and other characters
-                if !ch.is_whitespace() {
-                    eprintln!("Warning: Unknown character '{}' in formula", ch);
+        }
+        return;
+    }
+
+    // Test cases from the subject
+    let tests = [
+        ("AB|", true),
+        ("AB&", true),
+        ("AA!&", false),
+        ("AA^", false),
+        ("req_valid ack & req_valid ! &", false),
+        ("(req_valid -> ack) & req_valid", true),
+    ];
+
+    for (formula, expected) in tests {
+        let result = sat(formula);
+        println!("sat(\"{}\") = {}, expected: {}", formula, result, expected);
+        assert_eq!(result, expected, "Test failed for {}", formula);
+    }
+
+    // Past 32 variables, where `1 << num_vars` used to overflow:
+    // a chain of implications x0 > x1 > ... > x39, alone and with x0 & !x39
+    let chain: Vec<String> = (0..39).map(|i| format!("(x{} -> x{})", i, i + 1)).collect();
+    let chain = chain.join(" & ");
+    let large_tests = [
+        (chain.clone(), true),
+        (format!("{} & x0 & !x39", chain), false),
+    ];
+
+    for (formula, expected) in large_tests {
+        let result = sat(&formula);
+        println!("sat(<chain of 40 variables>) = {}, expected: {}", result, expected);
+        assert_eq!(result, expected, "Test failed for {}", formula);
+    }
+
+    // Pigeonhole: n + 1 pigeons never fit in n holes, which needs clause learning to prove quickly
+    for holes in 1..=7 {
+        let pigeon = |p: usize, h: usize| (p * holes + h + 1) as i32;
+        let mut solver = Solver::new();
+        for p in 0..=holes {
+            solver.add_clause(&(0..holes).map(|h| pigeon(p, h)).collect::<Vec<_>>());
+        }
+        for h in 0..holes {
+            for a in 0..=holes {
+                for b in a + 1..=holes {
+                    solver.add_clause(&[-pigeon(a, h), -pigeon(b, h)]);
                 }
             }
         }
+        let result = solver.solve();
+        println!("pigeonhole({} into {}) = {}, expected: false", holes + 1, holes, result);
+        assert!(!result, "Test failed for pigeonhole {}", holes);
     }
-    
-    // Should have exactly one value left on the stack
-    if stack.len() == 1 {
-        stack[0]
-    } else {
-        false // Invalid formula
+
+    // The compiled brute force agrees with the solver, and finds the first
+    // model in truth table order
+    for (formula, expected) in tests {
+        let f = boole::parse(formula).unwrap();
+        let result = sat::brute_force(&f);
+        if let SatResult::Sat(model) = &result {
+            assert!(f.eval(model), "Model does not satisfy {}", formula);
+        }
+        assert_eq!(result != SatResult::Unsat, expected, "Test failed for {}", formula);
     }
-}
+    // 40 variables, but the all-false row comes first
+    let SatResult::Sat(model) = sat::brute_force(&boole::parse(&chain).unwrap()) else { panic!("Test failed for the chain") };
+    assert!(model.len() == 40 && model.values().all(|&value| !value), "Test failed for the chain");
+    let first = sat::brute_force(&boole::parse("AB|C&").unwrap());
+    assert_eq!(first, SatResult::Sat([('A'.into(), false), ('B'.into(), true), ('C'.into(), true)].into()));
+    println!("brute_force(\"AB|C&\") = {:?}", first);
 
-fn main() {
-    // Test cases from the subject
-    println!("{}", sat("AB|"));   // Expected: true
-    println!("{}", sat("AB&"));   // Expected: true  
-    println!("{}", sat("AA!&"));  // Expected: false
-    println!("{}", sat("AA^"));   // Expected: false
+    // Tautology, contradiction or contingent, with assignments as witnesses
+    for (formula, expected) in [("AA!|", "tautology"), ("AA^", "contradiction"), ("AB|", "contingent"), ("AB>BC>&AC>>", "tautology"), ("1", "tautology"), ("0", "contradiction")] {
+        let f = boole::parse(formula).unwrap();
+        let result = sat::classify(&f);
+        println!("classify(\"{}\") = {}, expected: {}", formula, result, expected);
+        assert!(result.to_string().starts_with(expected), "Test failed for {}", formula);
+        match result {
+            Classification::Tautology => assert!(!sat::sat(&!f.clone()), "Test failed for {}", formula),
+            Classification::Contradiction { falsifying } => assert!(!f.eval(&falsifying), "Test failed for {}", formula),
+            Classification::Contingent { satisfying, falsifying } => {
+                assert!(f.eval(&satisfying) && !f.eval(&falsifying), "Test failed for {}", formula)
+            }
+        }
+    }
+    assert_eq!(sat::classify(&boole::parse("AA!&").unwrap()).to_string(), "contradiction (false for A=0)");
 }
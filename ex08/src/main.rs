@@ -1,16 +1,7 @@
+use boole::sets;
+
 fn powerset(set: Vec<i32>) -> Vec<Vec<i32>> {
-    let n = set.len();
-    let mut result = Vec::new();
-    for i in 0..(1 << n) {
-        let mut subset = Vec::new();
-        for j in 0..n {
-            if (i >> j) & 1 == 1 {
-                subset.push(set[j]);
-            }
-        }
-        result.push(subset);
-    }
-    result
+    sets::powerset(&set)
 }
 
 fn main() {
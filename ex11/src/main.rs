@@ -1,30 +1,10 @@
-fn map(x: u16, y: u16) -> f64 {
-    let mut z: u32 = 0;
-    for i in 0..16 {
-        z |= ((x as u32 >> i) & 1) << (2 * i);     // x bit to even positions
-        z |= ((y as u32 >> i) & 1) << (2 * i + 1); // y bit to odd positions
-    }
-    // Consistent normalization: divide by 2^32 - 1
-    (z as f64) / 4294967295.0
-}
+use boole::curve::map;
 
 fn reverse_map(n: f64) -> (u16, u16) {
-    if n < 0.0 || n > 1.0 {
+    boole::curve::reverse_map(n).unwrap_or_else(|| {
         eprintln!("Error: Input {} out of range [0, 1]", n);
-        return (0, 0);
-    }
-
-    // Convert n to 32-bit integer (consistent with map)
-    let z = (n * 4294967295.0).round() as u32;
-
-    // De-interleave bits to reconstruct x and y
-    let mut x: u16 = 0;
-    let mut y: u16 = 0;
-    for i in 0..16 {
-        x |= (((z >> (2 * i)) & 1) as u16) << i;     // Even bits to x
-        y |= (((z >> (2 * i + 1)) & 1) as u16) << i; // Odd bits to y
-    }
-    (x, y)
+        (0, 0)
+    })
 }
 
 fn main() {